@@ -20,6 +20,7 @@ fn main() -> Result<(), String> {
 
     println!("📋 URL Details:");
     println!("  Host: {}", url.host);
+    println!("  Port: {}", url.port);
     println!("  Path: {}", url.path);
     println!();
 
@@ -45,16 +46,17 @@ fn main() -> Result<(), String> {
 
             println!("📄 Raw HTML Body (first 500 characters):");
             println!("------------------------------------------");
-            let body_preview = if response.body.len() > 500 {
-                format!("{}...", &response.body[..500])
+            let body = response.text();
+            let body_preview = if body.len() > 500 {
+                format!("{}...", &body[..500])
             } else {
-                response.body.clone()
+                body.clone()
             };
             println!("{}", body_preview);
             println!();
 
             // Strip HTML tags and show clean text
-            let clean_text = strip_html_tags(&response.body);
+            let clean_text = strip_html_tags(&body);
             println!("🧹 Clean Text (HTML tags removed, first 300 characters):");
             println!("--------------------------------------------------------");
             let clean_preview = if clean_text.len() > 300 {
@@ -67,7 +69,7 @@ fn main() -> Result<(), String> {
 
             // Show some statistics
             println!("📊 Statistics:");
-            println!("  Original length: {} characters", response.body.len());
+            println!("  Original length: {} characters", body.len());
             println!("  Headers count: {}", response.headers.len());
         }
         Err(e) => {
@@ -0,0 +1,51 @@
+use learn_browser::url::{Url, raw_request};
+use std::env;
+use std::io::{self, IsTerminal, Read};
+
+fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        println!("💡 Usage: cargo run --example raw_request <url> < request.txt");
+        println!("   The request is read from stdin; lines are sent with CRLF endings.");
+        println!("   Without stdin, a minimal GET request for <url> is sent.");
+        return Ok(());
+    }
+
+    let url = Url::new(&args[1])?;
+
+    let mut input = String::new();
+    if !io::stdin().is_terminal() {
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Failed to read stdin: {}", e))?;
+    }
+
+    // Typed or piped requests usually have bare LF line endings; HTTP wants CRLF
+    let request = if input.is_empty() {
+        format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n",
+            url.request_target(),
+            url.authority()
+        )
+    } else {
+        input.lines().map(|line| format!("{}\r\n", line)).collect()
+    };
+
+    println!("📤 Sending {} bytes to {}:", request.len(), url.authority());
+    println!("------------------------------------------");
+    print!("{}", request.replace("\r\n", "␍␊\n"));
+    println!("------------------------------------------\n");
+
+    let response = raw_request(&url, request.as_bytes())?;
+
+    println!("📥 Received {} bytes:", response.bytes.len());
+    println!("------------------------------------------");
+    println!("{}", String::from_utf8_lossy(&response.bytes));
+    println!("------------------------------------------\n");
+
+    println!("⏱️  Timing:");
+    println!("  Connect: {:?}", response.connect_time);
+    println!("  Total:   {:?}", response.total_time);
+
+    Ok(())
+}
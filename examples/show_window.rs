@@ -1,10 +1,59 @@
 use eframe::egui::{self, UiBuilder};
-use learn_browser::url::{Url, request, strip_html_tags};
+use learn_browser::cache::{DEFAULT_MAX_CACHE_SIZE, default_cache_directory, enable_disk_cache};
+use learn_browser::content_type::{ContentKind, content_kind, mime_essence};
+use learn_browser::feed::{feed_to_html, is_feed_content_type};
+use learn_browser::gemini::{
+    InputPrompt, gemtext_to_html, input_prompt, is_gemtext_content_type, url_with_input,
+};
+use learn_browser::markdown::{is_markdown_content_type, is_markdown_path, markdown_to_html};
+use learn_browser::socket::{Throttle, set_known_hosts_file, set_throttle};
+use learn_browser::url::{Scheme, Url, request, strip_html_tags};
+use std::env;
+use std::path::Path;
+use std::time::Duration;
 
 const WIDTH: f32 = 800.0;
 const HEIGHT: f32 = 600.0;
+const DEFAULT_URL: &str = "about:blank";
+
+// Network conditions offered by the devtools panel (F12)
+const THROTTLE_PRESETS: [(&str, Option<Throttle>); 3] = [
+    ("No throttling", None),
+    (
+        "Fast 3G",
+        Some(Throttle {
+            bytes_per_second: Some(180_000),
+            latency: Duration::from_millis(150),
+        }),
+    ),
+    (
+        "Slow 3G",
+        Some(Throttle {
+            bytes_per_second: Some(50_000),
+            latency: Duration::from_millis(400),
+        }),
+    ),
+];
+
+// The page to open: a URL or local file path from the command line, or the default URL
+fn start_url() -> Result<Url, String> {
+    match env::args().nth(1) {
+        Some(arg) if Path::new(&arg).exists() => Url::from_file_path(Path::new(&arg)),
+        Some(arg) => Url::new(&arg),
+        None => Url::new(DEFAULT_URL),
+    }
+}
 
 fn main() -> eframe::Result {
+    if let Some(directory) = default_cache_directory() {
+        if let Err(e) = enable_disk_cache(&directory, DEFAULT_MAX_CACHE_SIZE) {
+            eprintln!("Disk cache disabled: {}", e);
+        }
+        if let Err(e) = set_known_hosts_file(&directory.join("known_hosts")) {
+            eprintln!("Gemini certificates won't be remembered: {}", e);
+        }
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([WIDTH, HEIGHT]),
         ..Default::default()
@@ -38,6 +87,11 @@ struct BrowserApp {
     text_content: String,
     error_message: Option<String>,
     scroll_offset: f32,
+    devtools_open: bool,
+    throttle_preset: usize,
+    // A Gemini page asking for input, and what has been typed so far
+    input_request: Option<(Url, InputPrompt)>,
+    input_text: String,
 }
 
 impl Default for BrowserApp {
@@ -46,21 +100,63 @@ impl Default for BrowserApp {
             text_content: String::new(),
             error_message: None,
             scroll_offset: 0.0,
+            devtools_open: false,
+            throttle_preset: 0,
+            input_request: None,
+            input_text: String::new(),
         };
-        app.fetch_content();
+        app.fetch_content(start_url());
         app
     }
 }
 
 impl BrowserApp {
-    fn fetch_content(&mut self) {
+    fn fetch_content(&mut self, url: Result<Url, String>) {
         self.error_message = None;
+        self.scroll_offset = 0.0;
+        self.input_request = None;
+        self.input_text.clear();
 
-        match Url::new("https://browser.engineering/examples/xiyouji.html") {
+        match url {
             Ok(url) => match request(&url) {
+                Ok(response) if url.view_source => {
+                    self.text_content = response.text();
+                }
                 Ok(response) => {
-                    let clean_text = strip_html_tags(&response.body);
-                    self.text_content = clean_text;
+                    if let Some(prompt) = input_prompt(&response) {
+                        self.text_content = prompt.prompt.clone();
+                        self.input_request = Some((url, prompt));
+                        return;
+                    }
+                    let content_type = response.sniffed_content_type();
+                    let body = response.text();
+                    self.text_content = if is_feed_content_type(&content_type) {
+                        match feed_to_html(&body) {
+                            Ok(html) => strip_html_tags(&html),
+                            Err(e) => {
+                                self.error_message = Some(format!("Feed parsing failed: {}", e));
+                                return;
+                            }
+                        }
+                    } else if is_gemtext_content_type(&content_type) {
+                        strip_html_tags(&gemtext_to_html(&body))
+                    } else if is_markdown_content_type(&content_type)
+                        // Only local files are recognized by extension; a server's
+                        // Content-Type is taken at its word
+                        || (url.scheme == Scheme::File && is_markdown_path(&url.path))
+                    {
+                        strip_html_tags(&markdown_to_html(&body))
+                    } else {
+                        match content_kind(&content_type) {
+                            ContentKind::Html => strip_html_tags(&body),
+                            ContentKind::PlainText => body,
+                            ContentKind::Image | ContentKind::Binary => format!(
+                                "[{} content, {} bytes]",
+                                mime_essence(&content_type),
+                                response.body.len()
+                            ),
+                        }
+                    };
                 }
                 Err(e) => {
                     self.error_message = Some(format!("Request failed: {}", e));
@@ -73,8 +169,63 @@ impl BrowserApp {
     }
 }
 
+// A file dropped on the window carries a path on native platforms; on the web
+// only its name is known, which is a URL when a link was dropped
+fn dropped_url(file: &egui::DroppedFile) -> Result<Url, String> {
+    match &file.path {
+        Some(path) => Url::from_file_path(path),
+        None => Url::new(&file.name),
+    }
+}
+
 impl eframe::App for BrowserApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // A drop should open in a new tab, but there are no tabs yet, so it
+        // replaces the current page for now
+        let dropped = ctx.input(|i| i.raw.dropped_files.first().map(dropped_url));
+        if let Some(url) = dropped {
+            self.fetch_content(url);
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            self.devtools_open = !self.devtools_open;
+        }
+        let mut devtools_open = self.devtools_open;
+        egui::Window::new("Devtools")
+            .open(&mut devtools_open)
+            .show(ctx, |ui| {
+                ui.label("Network throttling");
+                for (index, (name, throttle)) in THROTTLE_PRESETS.iter().enumerate() {
+                    if ui
+                        .radio_value(&mut self.throttle_preset, index, *name)
+                        .changed()
+                    {
+                        set_throttle(*throttle);
+                    }
+                }
+            });
+        self.devtools_open = devtools_open;
+
+        let mut submitted = None;
+        if let Some((url, prompt)) = &self.input_request {
+            egui::TopBottomPanel::top("input_prompt").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(&prompt.prompt);
+                    let field = ui.add(
+                        egui::TextEdit::singleline(&mut self.input_text).password(prompt.sensitive),
+                    );
+                    let entered =
+                        field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("Submit").clicked() || entered {
+                        submitted = Some(url_with_input(url, &self.input_text));
+                    }
+                });
+            });
+        }
+        if let Some(url) = submitted {
+            self.fetch_content(Ok(url));
+        }
+
         if ctx.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
             self.scroll_offset += 100.0;
         }
@@ -112,5 +263,21 @@ impl eframe::App for BrowserApp {
                 }
             }
         });
+
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let screen = ctx.screen_rect();
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("drop_target"),
+            ));
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop to open",
+                egui::FontId::proportional(24.0),
+                egui::Color32::WHITE,
+            );
+        }
     }
 }
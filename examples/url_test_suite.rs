@@ -0,0 +1,78 @@
+use learn_browser::url::Url;
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::panic;
+
+// Runs the web-platform-tests URL corpus (url/resources/urltestdata.json)
+// against Url::new and Url::join and reports how many cases agree.
+fn main() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        println!("💡 Usage: cargo run --example url_test_suite <urltestdata.json> [--verbose]");
+        println!("   Download the corpus from");
+        println!(
+            "   https://github.com/web-platform-tests/wpt/blob/master/url/resources/urltestdata.json"
+        );
+        return Ok(());
+    }
+    let verbose = args.iter().any(|arg| arg == "--verbose");
+
+    let text =
+        fs::read_to_string(&args[1]).map_err(|e| format!("Failed to read {}: {}", args[1], e))?;
+    let corpus: Value =
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", args[1], e))?;
+    let cases = corpus
+        .as_array()
+        .ok_or_else(|| "Expected a JSON array of test cases".to_string())?;
+
+    // Parser bugs may panic on odd input; count those as failures instead of aborting
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut passed = 0;
+    let mut failed = 0;
+    // Plain strings in the corpus are comments
+    for case in cases.iter().filter(|case| case.is_object()) {
+        let input = case["input"].as_str().unwrap_or_default();
+        let base = case["base"].as_str();
+        let expect_failure = case["failure"].as_bool().unwrap_or(false);
+
+        let result = panic::catch_unwind(|| match base {
+            Some(base) => Url::new(base).and_then(|base| base.join(input)),
+            None => Url::new(input),
+        })
+        .unwrap_or_else(|_| Err("panicked".to_string()))
+        .map(|url| url.to_string());
+
+        let outcome = match (&result, expect_failure) {
+            (Err(_), true) => Ok(()),
+            (Ok(href), true) => Err(format!("expected failure, got {}", href)),
+            (Err(e), false) => Err(format!("expected {}, got error: {}", case["href"], e)),
+            (Ok(href), false) if case["href"].as_str() == Some(href) => Ok(()),
+            (Ok(href), false) => Err(format!("expected {}, got {:?}", case["href"], href)),
+        };
+
+        match outcome {
+            Ok(()) => passed += 1,
+            Err(reason) => {
+                failed += 1;
+                if verbose {
+                    println!("❌ {:?} (base {:?}): {}", input, base, reason);
+                }
+            }
+        }
+    }
+
+    let total = passed + failed;
+    println!("✅ Passed: {}", passed);
+    println!("❌ Failed: {}", failed);
+    if total > 0 {
+        println!(
+            "📊 {:.1}% of {} cases",
+            passed as f64 * 100.0 / total as f64,
+            total
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::scheme::DEFAULT_REGISTRY;
+use crate::url::{HttpResponse, Url};
+use std::collections::HashMap;
+
+fn html_response(status: u16, explanation: &str, body: String) -> HttpResponse {
+    let mut headers = HashMap::new();
+    headers.insert("content-type".to_string(), "text/html".to_string());
+    headers.insert("content-length".to_string(), body.len().to_string());
+
+    HttpResponse {
+        version: "HTTP/1.0".to_string(),
+        status,
+        explanation: explanation.to_string(),
+        headers,
+        body: body.into_bytes(),
+    }
+}
+
+fn version_page() -> String {
+    format!(
+        "<html><head><title>About {name}</title></head><body>\n\
+         <h1>{name}</h1>\n\
+         <p>Version {version}</p>\n\
+         <p>Supported schemes: {schemes}</p>\n\
+         </body></html>\n",
+        name = env!("CARGO_PKG_NAME"),
+        version = env!("CARGO_PKG_VERSION"),
+        schemes = DEFAULT_REGISTRY.schemes().join(", "),
+    )
+}
+
+pub fn request(url: &Url) -> Result<HttpResponse, String> {
+    match url.path.as_str() {
+        "blank" => Ok(html_response(200, "OK", String::new())),
+        "version" => Ok(html_response(200, "OK", version_page())),
+        page => Ok(html_response(
+            404,
+            "Not Found",
+            format!(
+                "<html><body><h1>Not Found</h1><p>Unknown page: about:{}</p></body></html>\n",
+                page
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_about_blank() {
+        let url = Url::new("about:blank").unwrap();
+        let response = request(&url).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.headers.get("content-type"),
+            Some(&"text/html".to_string())
+        );
+        assert_eq!(response.text(), "");
+    }
+
+    #[test]
+    fn test_about_version() {
+        let url = Url::new("about:version").unwrap();
+        let response = request(&url).unwrap();
+
+        assert_eq!(response.status, 200);
+        let text = response.text();
+        assert!(text.contains("<h1>learn-browser</h1>"));
+        assert!(text.contains(&format!("Version {}", env!("CARGO_PKG_VERSION"))));
+        assert!(text.contains("about, data, file, gemini, http, https"));
+    }
+
+    #[test]
+    fn test_about_unknown_page() {
+        let url = Url::new("about:nothing").unwrap();
+        let response = request(&url).unwrap();
+
+        assert_eq!(response.status, 404);
+        assert!(response.text().contains("Unknown page: about:nothing"));
+    }
+}
@@ -0,0 +1,80 @@
+use encoding_rs::{Encoding, UTF_8};
+
+// The charset parameter of a Content-Type value, e.g. "Shift_JIS" in
+// "text/html; charset=Shift_JIS"
+pub fn content_type_charset(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"').trim();
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+// Decodes a body with the charset its Content-Type declares. A byte order
+// mark takes precedence, and an unknown or missing charset falls back to
+// UTF-8; malformed sequences become U+FFFD either way.
+pub fn decode_text(bytes: &[u8], content_type: &str) -> String {
+    let encoding = content_type_charset(content_type)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "日本語" in each encoding
+    const SHIFT_JIS: &[u8] = &[0x93, 0xfa, 0x96, 0x7b, 0x8c, 0xea];
+    const EUC_JP: &[u8] = &[0xc6, 0xfc, 0xcb, 0xdc, 0xb8, 0xec];
+
+    #[test]
+    fn test_content_type_charset() {
+        assert_eq!(
+            content_type_charset("text/html; charset=Shift_JIS"),
+            Some("Shift_JIS".to_string())
+        );
+        assert_eq!(
+            content_type_charset("text/html;CHARSET=\"euc-jp\""),
+            Some("euc-jp".to_string())
+        );
+        assert_eq!(content_type_charset("text/html"), None);
+        assert_eq!(content_type_charset("text/html; charset="), None);
+    }
+
+    #[test]
+    fn test_decode_japanese_charsets() {
+        assert_eq!(
+            decode_text(SHIFT_JIS, "text/html; charset=Shift_JIS"),
+            "日本語"
+        );
+        assert_eq!(
+            decode_text(SHIFT_JIS, "text/html; charset=x-sjis"),
+            "日本語"
+        );
+        assert_eq!(decode_text(EUC_JP, "text/html; charset=EUC-JP"), "日本語");
+    }
+
+    #[test]
+    fn test_decode_falls_back_to_utf8() {
+        assert_eq!(decode_text("日本語".as_bytes(), "text/html"), "日本語");
+        assert_eq!(
+            decode_text(b"ok", "text/plain; charset=no-such-charset"),
+            "ok"
+        );
+        assert_eq!(
+            decode_text(SHIFT_JIS, ""),
+            "\u{fffd}\u{fffd}\u{fffd}{\u{fffd}\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn test_byte_order_mark_wins() {
+        let bytes = [0xef, 0xbb, 0xbf, b'h', b'i'];
+        assert_eq!(decode_text(&bytes, "text/plain; charset=Shift_JIS"), "hi");
+    }
+}
@@ -0,0 +1,89 @@
+use brotli_decompressor::Decompressor;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::io::Read;
+
+// Sent as Accept-Encoding; every coding listed here can be decoded below
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+fn read_all<R: Read>(mut decoder: R, coding: &str) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    decoder
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("Failed to decode {} body: {}", coding, e))?;
+    Ok(decoded)
+}
+
+fn decode_coding(coding: &str, body: Vec<u8>) -> Result<Vec<u8>, String> {
+    match coding {
+        "identity" | "" => Ok(body),
+        "gzip" | "x-gzip" => read_all(GzDecoder::new(&body[..]), coding),
+        "deflate" => read_all(ZlibDecoder::new(&body[..]), coding),
+        "br" => read_all(Decompressor::new(&body[..], BROTLI_BUFFER_SIZE), coding),
+        _ => Err(format!("Unsupported content encoding: {}", coding)),
+    }
+}
+
+// Undoes a Content-Encoding header value. Codings are listed in the order
+// they were applied, so they are removed from last to first.
+pub fn decode_body(content_encoding: &str, body: Vec<u8>) -> Result<Vec<u8>, String> {
+    content_encoding
+        .split(',')
+        .map(|coding| coding.trim().to_lowercase())
+        .rev()
+        .try_fold(body, |body, coding| decode_coding(&coding, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    // "hello, brotli" as produced by `brotli -c`
+    const BROTLI_HELLO: &[u8] = &[
+        0x0b, 0x06, 0x80, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x62, 0x72, 0x6f, 0x74, 0x6c,
+        0x69, 0x03,
+    ];
+
+    #[test]
+    fn test_decode_identity() {
+        assert_eq!(decode_body("identity", b"plain".to_vec()).unwrap(), b"plain");
+    }
+
+    #[test]
+    fn test_decode_gzip() {
+        let body = gzip(b"hello, gzip");
+        assert_eq!(decode_body("gzip", body.clone()).unwrap(), b"hello, gzip");
+        assert_eq!(decode_body("X-Gzip", body).unwrap(), b"hello, gzip");
+    }
+
+    #[test]
+    fn test_decode_brotli() {
+        assert_eq!(
+            decode_body("br", BROTLI_HELLO.to_vec()).unwrap(),
+            b"hello, brotli"
+        );
+    }
+
+    #[test]
+    fn test_decode_stacked_codings() {
+        // gzip was applied first, so it is undone last
+        let body = gzip(BROTLI_HELLO);
+        assert_eq!(decode_body("br, gzip", body).unwrap(), b"hello, brotli");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode_body("gzip", b"not gzip".to_vec()).is_err());
+        assert!(decode_body("compress", b"data".to_vec()).is_err());
+    }
+}
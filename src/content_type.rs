@@ -0,0 +1,108 @@
+use crate::url::HttpResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentKind {
+    Html,
+    // Shown verbatim, without looking for markup
+    PlainText,
+    Image,
+    // Anything that isn't meant to be read as text
+    Binary,
+}
+
+// The lowercase "type/subtype" of a Content-Type value, without parameters
+pub fn mime_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase()
+}
+
+pub fn content_kind(content_type: &str) -> ContentKind {
+    let essence = mime_essence(content_type);
+    match essence.as_str() {
+        "text/html" | "application/xhtml+xml" => ContentKind::Html,
+        "application/json"
+        | "application/xml"
+        | "application/javascript"
+        | "application/ecmascript" => ContentKind::PlainText,
+        _ if essence.starts_with("image/") => ContentKind::Image,
+        _ if essence.starts_with("text/")
+            || essence.ends_with("+xml")
+            || essence.ends_with("+json") =>
+        {
+            ContentKind::PlainText
+        }
+        _ => ContentKind::Binary,
+    }
+}
+
+// Classifies by the sniffed content type, so a missing or wrong
+// Content-Type doesn't put binary data in front of the reader
+pub fn classify_response(response: &HttpResponse) -> ContentKind {
+    content_kind(&response.sniffed_content_type())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn response(content_type: Option<&str>, body: &[u8]) -> HttpResponse {
+        let mut headers = HashMap::new();
+        if let Some(content_type) = content_type {
+            headers.insert("content-type".to_string(), content_type.to_string());
+        }
+        HttpResponse {
+            version: "HTTP/1.1".to_string(),
+            status: 200,
+            explanation: "OK".to_string(),
+            headers,
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_mime_essence() {
+        assert_eq!(mime_essence(" Text/HTML ; charset=utf-8"), "text/html");
+        assert_eq!(mime_essence(""), "");
+    }
+
+    #[test]
+    fn test_content_kind() {
+        assert_eq!(content_kind("text/html; charset=utf-8"), ContentKind::Html);
+        assert_eq!(content_kind("application/xhtml+xml"), ContentKind::Html);
+        assert_eq!(content_kind("text/plain"), ContentKind::PlainText);
+        assert_eq!(content_kind("text/css"), ContentKind::PlainText);
+        assert_eq!(content_kind("application/json"), ContentKind::PlainText);
+        assert_eq!(content_kind("image/svg+xml"), ContentKind::Image);
+        assert_eq!(content_kind("image/png"), ContentKind::Image);
+        assert_eq!(content_kind("application/pdf"), ContentKind::Binary);
+        assert_eq!(
+            content_kind("application/octet-stream"),
+            ContentKind::Binary
+        );
+    }
+
+    #[test]
+    fn test_classify_response() {
+        assert_eq!(
+            classify_response(&response(Some("text/plain"), b"<p>as text</p>")),
+            ContentKind::PlainText
+        );
+        assert_eq!(
+            classify_response(&response(None, b"<p>a page</p>")),
+            ContentKind::Html
+        );
+        assert_eq!(
+            classify_response(&response(Some("text/plain"), b"\xff\xd8\xff\xe0")),
+            ContentKind::Image
+        );
+        assert_eq!(
+            classify_response(&response(None, b"\x7fELF\x02\x01\x01\x00")),
+            ContentKind::Binary
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::url::{HttpResponse, Url, percent_decode};
+use std::collections::HashMap;
+
+const DEFAULT_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+
+fn base64_value(byte: u8) -> Option<u32> {
+    match byte {
+        b'A'..=b'Z' => Some((byte - b'A') as u32),
+        b'a'..=b'z' => Some((byte - b'a') as u32 + 26),
+        b'0'..=b'9' => Some((byte - b'0') as u32 + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+pub fn base64_decode(text: &str) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in text.bytes() {
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+        if byte == b'=' {
+            break;
+        }
+        let value = base64_value(byte)
+            .ok_or_else(|| format!("Invalid base64 character: {}", byte as char))?;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Ok(decoded)
+}
+
+pub fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// Splits "data:[<media type>][;base64],<data>" into its media type and decoded payload
+pub fn parse_data_url(url: &Url) -> Result<(String, Vec<u8>), String> {
+    let (header, payload) = url
+        .path
+        .split_once(',')
+        .ok_or_else(|| "Invalid data URL: missing comma".to_string())?;
+
+    let header = header.trim();
+    let (media_type, is_base64) = match header.rsplit_once(';') {
+        Some((media_type, encoding)) if encoding.trim().eq_ignore_ascii_case("base64") => {
+            (media_type.trim(), true)
+        }
+        _ => (header, false),
+    };
+
+    let media_type = if media_type.is_empty() {
+        DEFAULT_MEDIA_TYPE.to_string()
+    } else if media_type.starts_with(';') {
+        // Parameters without a type, as in "data:;charset=utf-8,", apply to text/plain
+        format!("text/plain{}", media_type)
+    } else {
+        media_type.to_string()
+    };
+
+    let payload = percent_decode(payload);
+    let body = if is_base64 {
+        base64_decode(&String::from_utf8_lossy(&payload))?
+    } else {
+        payload
+    };
+
+    Ok((media_type, body))
+}
+
+pub fn request(url: &Url) -> Result<HttpResponse, String> {
+    let (media_type, body) = parse_data_url(url)?;
+
+    let mut headers = HashMap::new();
+    headers.insert("content-type".to_string(), media_type);
+    headers.insert("content-length".to_string(), body.len().to_string());
+
+    Ok(HttpResponse {
+        version: "HTTP/1.0".to_string(),
+        status: 200,
+        explanation: "OK".to_string(),
+        headers,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(
+            base64_decode("SGVsbG8sIFdvcmxkIQ==").unwrap(),
+            b"Hello, World!"
+        );
+        assert_eq!(base64_decode("YQ==").unwrap(), b"a");
+        assert_eq!(base64_decode("YWI=").unwrap(), b"ab");
+        assert_eq!(base64_decode("YWJj").unwrap(), b"abc");
+        assert_eq!(base64_decode("YW Jj\n").unwrap(), b"abc");
+        assert_eq!(base64_decode("").unwrap(), b"");
+        assert_eq!(
+            base64_decode("YW*j").unwrap_err(),
+            "Invalid base64 character: *"
+        );
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b"Hello, World!"), "SGVsbG8sIFdvcmxkIQ==");
+        assert_eq!(base64_encode(b"a"), "YQ==");
+        assert_eq!(base64_encode(b"ab"), "YWI=");
+        assert_eq!(base64_encode(b"abc"), "YWJj");
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(
+            base64_decode(&base64_encode(&[0, 255, 128, 7])).unwrap(),
+            vec![0, 255, 128, 7]
+        );
+    }
+
+    #[test]
+    fn test_data_request_plain() {
+        let url = Url::new("data:text/html,<h1>Hello%20there</h1>").unwrap();
+
+        let response = request(&url).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.headers.get("content-type"),
+            Some(&"text/html".to_string())
+        );
+        assert_eq!(response.text(), "<h1>Hello there</h1>");
+    }
+
+    #[test]
+    fn test_data_request_base64() {
+        let url = Url::new("data:text/plain;charset=utf-8;base64,5pel5pys6Kqe").unwrap();
+
+        let response = request(&url).unwrap();
+
+        assert_eq!(
+            response.headers.get("content-type"),
+            Some(&"text/plain;charset=utf-8".to_string())
+        );
+        assert_eq!(
+            response.headers.get("content-length"),
+            Some(&"9".to_string())
+        );
+        assert_eq!(response.text(), "日本語");
+    }
+
+    #[test]
+    fn test_data_request_default_media_type() {
+        let url = Url::new("data:,just%20text").unwrap();
+        let (media_type, body) = parse_data_url(&url).unwrap();
+        assert_eq!(media_type, "text/plain;charset=US-ASCII");
+        assert_eq!(body, b"just text");
+
+        let url = Url::new("data:;charset=utf-8,x").unwrap();
+        let (media_type, _) = parse_data_url(&url).unwrap();
+        assert_eq!(media_type, "text/plain;charset=utf-8");
+    }
+
+    #[test]
+    fn test_data_request_missing_comma() {
+        let url = Url::new("data:text/html").unwrap();
+        assert_eq!(
+            request(&url).unwrap_err(),
+            "Invalid data URL: missing comma"
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use crate::url::escape_html;
+
+#[derive(Debug, PartialEq)]
+pub struct FeedEntry {
+    pub title: String,
+    pub link: Option<String>,
+    pub date: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Feed {
+    pub title: String,
+    pub entries: Vec<FeedEntry>,
+}
+
+pub fn is_feed_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+    mime == "application/rss+xml" || mime == "application/atom+xml"
+}
+
+pub fn parse_feed(xml: &str) -> Result<Feed, String> {
+    if let Some(channel) = element_content(xml, "channel") {
+        // RSS 2.0: <channel> holds the feed title followed by <item> entries
+        let header = channel.split("<item").next().unwrap_or("");
+        let title = element_content(header, "title")
+            .map(unescape_xml_text)
+            .unwrap_or_default();
+        let entries = elements(channel, "item")
+            .into_iter()
+            .map(|item| FeedEntry {
+                title: element_content(item, "title")
+                    .map(unescape_xml_text)
+                    .unwrap_or_default(),
+                link: element_content(item, "link").map(unescape_xml_text),
+                date: element_content(item, "pubDate").map(unescape_xml_text),
+            })
+            .collect();
+        Ok(Feed { title, entries })
+    } else if let Some(feed) = element_content(xml, "feed") {
+        // Atom: <feed> holds the feed title followed by <entry> elements
+        let header = feed.split("<entry").next().unwrap_or("");
+        let title = element_content(header, "title")
+            .map(unescape_xml_text)
+            .unwrap_or_default();
+        let entries = elements(feed, "entry")
+            .into_iter()
+            .map(|entry| FeedEntry {
+                title: element_content(entry, "title")
+                    .map(unescape_xml_text)
+                    .unwrap_or_default(),
+                link: atom_link(entry),
+                date: element_content(entry, "updated")
+                    .or_else(|| element_content(entry, "published"))
+                    .map(unescape_xml_text),
+            })
+            .collect();
+        Ok(Feed { title, entries })
+    } else {
+        Err("Invalid feed: missing <channel> or <feed> element".to_string())
+    }
+}
+
+pub fn feed_to_html(xml: &str) -> Result<String, String> {
+    let feed = parse_feed(xml)?;
+
+    let mut html = String::new();
+    html.push_str(&format!(
+        "<html><head><title>{}</title></head><body>\n",
+        escape_html(&feed.title)
+    ));
+    html.push_str(&format!("<h1>{}</h1>\n<ul>\n", escape_html(&feed.title)));
+    for entry in &feed.entries {
+        html.push_str("<li>");
+        match &entry.link {
+            Some(link) => html.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(link),
+                escape_html(&entry.title)
+            )),
+            None => html.push_str(&escape_html(&entry.title)),
+        }
+        if let Some(date) = &entry.date {
+            html.push_str(&format!(" <time>{}</time>", escape_html(date)));
+        }
+        html.push_str("</li>\n");
+    }
+    html.push_str("</ul>\n</body></html>\n");
+
+    Ok(html)
+}
+
+fn atom_link(entry: &str) -> Option<String> {
+    let mut rest = entry;
+    let mut fallback = None;
+    while let Some(start) = find_open_tag(rest, "link") {
+        let tag_end = rest[start..].find('>')? + start;
+        let tag = &rest[start..tag_end];
+        if let Some(href) = attribute(tag, "href") {
+            match attribute(tag, "rel").as_deref() {
+                None | Some("alternate") => return Some(href),
+                _ => {
+                    fallback.get_or_insert(href);
+                }
+            }
+        }
+        rest = &rest[tag_end..];
+    }
+    fallback
+}
+
+// Scans the attributes after the tag name, which may be separated by any
+// whitespace (including line breaks) and have whitespace around `=`
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let is_name_end = |c: char| c.is_ascii_whitespace() || matches!(c, '=' | '/' | '>');
+    let mut rest = tag.trim_start_matches('<');
+    rest = &rest[rest.find(is_name_end).unwrap_or(rest.len())..];
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        let name_end = rest.find(is_name_end).unwrap_or(rest.len());
+        if name_end == 0 {
+            return None;
+        }
+        let attribute_name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let mut value = None;
+        if let Some(after_equals) = rest.strip_prefix('=') {
+            rest = after_equals.trim_start();
+            let value_end = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let end = rest[1..].find(quote)? + 1;
+                    value = Some(&rest[1..end]);
+                    end + 1
+                }
+                _ => {
+                    let end = rest
+                        .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                        .unwrap_or(rest.len());
+                    value = Some(&rest[..end]);
+                    end
+                }
+            };
+            rest = &rest[value_end..];
+        }
+
+        if attribute_name == name {
+            return Some(unescape_xml_text(value.unwrap_or("")));
+        }
+    }
+}
+
+// Finds the start of `<tag` followed by whitespace, `>` or `/`,
+// so that `<title` doesn't match `<titles>`.
+fn find_open_tag(xml: &str, tag: &str) -> Option<usize> {
+    let pattern = format!("<{}", tag);
+    let mut offset = 0;
+    while let Some(pos) = xml[offset..].find(&pattern) {
+        let start = offset + pos;
+        let after = start + pattern.len();
+        match xml[after..].chars().next() {
+            Some(c) if c == '>' || c == '/' || c.is_whitespace() => return Some(start),
+            _ => offset = after,
+        }
+    }
+    None
+}
+
+// Returns the byte range of the element's content and the offset just past it.
+fn element_span(xml: &str, tag: &str) -> Option<(usize, usize, usize)> {
+    let start = find_open_tag(xml, tag)?;
+    let open_end = xml[start..].find('>')? + start;
+    if xml[..open_end].ends_with('/') {
+        return Some((open_end, open_end, open_end + 1));
+    }
+    let close = format!("</{}>", tag);
+    let content_end = xml[open_end + 1..].find(&close)? + open_end + 1;
+    Some((open_end + 1, content_end, content_end + close.len()))
+}
+
+fn element_content<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let (start, end, _) = element_span(xml, tag)?;
+    Some(&xml[start..end])
+}
+
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let mut result = Vec::new();
+    let mut rest = xml;
+    while let Some((start, end, after)) = element_span(rest, tag) {
+        result.push(&rest[start..end]);
+        rest = &rest[after..];
+    }
+    result
+}
+
+// The text of an element or attribute with CDATA unwrapped and the
+// predefined and numeric character references (&amp;, &#8217;, &#x2014;) replaced
+fn unescape_xml_text(text: &str) -> String {
+    let text = text.trim();
+    if let Some(cdata) = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|t| t.strip_suffix("]]>"))
+    {
+        return cdata.to_string();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .and_then(|end| Some((decode_reference(&rest[1..end])?, end)));
+        match decoded {
+            Some((character, end)) => {
+                result.push(character);
+                rest = &rest[end + 1..];
+            }
+            // Not a reference; kept as written
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn decode_reference(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "amp" => Some('&'),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0">
+  <channel>
+    <title>Example &amp; Co</title>
+    <link>http://example.com/</link>
+    <item>
+      <title>First post</title>
+      <link>http://example.com/first</link>
+      <pubDate>Mon, 06 Sep 2021 16:45:00 +0000</pubDate>
+    </item>
+    <item>
+      <title><![CDATA[Second <post>]]></title>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom Example</title>
+  <link href="http://example.org/"/>
+  <entry>
+    <title>Atom entry</title>
+    <link rel="edit" href="http://example.org/edit/1"/>
+    <link rel="alternate" href="http://example.org/2003/12/13/atom03"/>
+    <updated>2003-12-13T18:30:02Z</updated>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_is_feed_content_type() {
+        assert!(is_feed_content_type("application/rss+xml"));
+        assert!(is_feed_content_type("application/atom+xml; charset=utf-8"));
+        assert!(is_feed_content_type("Application/RSS+XML"));
+        assert!(!is_feed_content_type("text/html"));
+        assert!(!is_feed_content_type("application/xml"));
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse_feed(RSS).unwrap();
+        assert_eq!(feed.title, "Example & Co");
+        assert_eq!(feed.entries.len(), 2);
+        assert_eq!(
+            feed.entries[0],
+            FeedEntry {
+                title: "First post".to_string(),
+                link: Some("http://example.com/first".to_string()),
+                date: Some("Mon, 06 Sep 2021 16:45:00 +0000".to_string()),
+            }
+        );
+        assert_eq!(feed.entries[1].title, "Second <post>");
+        assert_eq!(feed.entries[1].link, None);
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse_feed(ATOM).unwrap();
+        assert_eq!(feed.title, "Atom Example");
+        assert_eq!(feed.entries.len(), 1);
+        assert_eq!(feed.entries[0].title, "Atom entry");
+        assert_eq!(
+            feed.entries[0].link,
+            Some("http://example.org/2003/12/13/atom03".to_string())
+        );
+        assert_eq!(
+            feed.entries[0].date,
+            Some("2003-12-13T18:30:02Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_atom_link_multiline_attributes() {
+        let entry = "<entry>\n\
+                     <link rel=\"edit\"\thref='http://example.org/edit'/>\n\
+                     <link rel=\"alternate\"\n  href = \"http://example.org/post?a=1&amp;b=2\"/>\n\
+                     </entry>";
+        assert_eq!(
+            atom_link(entry),
+            Some("http://example.org/post?a=1&b=2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_attribute() {
+        let tag = "<link\n  rel=alternate\thref = 'x.html' hreflang=\"en\"";
+        assert_eq!(attribute(tag, "href"), Some("x.html".to_string()));
+        assert_eq!(attribute(tag, "rel"), Some("alternate".to_string()));
+        // Attribute names match whole names only
+        assert_eq!(attribute(tag, "lang"), None);
+        assert_eq!(attribute(tag, "link"), None);
+    }
+
+    #[test]
+    fn test_unescape_xml_text() {
+        assert_eq!(
+            unescape_xml_text("It&#8217;s here &#x2014; &amp;lt;b&gt;"),
+            "It\u{2019}s here \u{2014} &lt;b>"
+        );
+        assert_eq!(unescape_xml_text("<![CDATA[&#38;]]>"), "&#38;");
+        // Anything that isn't a reference is left alone
+        assert_eq!(
+            unescape_xml_text("a & b &nbsp; &#xZZ; &#1114112;"),
+            "a & b &nbsp; &#xZZ; &#1114112;"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_feed() {
+        let result = parse_feed("<html><body>Not a feed</body></html>");
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            "Invalid feed: missing <channel> or <feed> element"
+        );
+    }
+
+    #[test]
+    fn test_feed_to_html() {
+        let html = feed_to_html(RSS).unwrap();
+        assert!(html.contains("<h1>Example &amp; Co</h1>"));
+        assert!(html.contains(
+            "<li><a href=\"http://example.com/first\">First post</a> <time>Mon, 06 Sep 2021 16:45:00 +0000</time></li>"
+        ));
+        assert!(html.contains("<li>Second &lt;post&gt;</li>"));
+    }
+}
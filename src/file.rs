@@ -0,0 +1,153 @@
+use crate::url::{HttpResponse, Url, percent_decode};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn guess_content_type(path: &str) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "rss" => "application/rss+xml",
+        "atom" => "application/atom+xml",
+        "txt" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "gmi" => "text/gemini",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+pub fn to_file_path(url: &Url) -> Result<PathBuf, String> {
+    if !url.host.is_empty() && !url.host.eq_ignore_ascii_case("localhost") {
+        return Err(format!("Unsupported file URL host: {}", url.host));
+    }
+    let path = percent_decode(&url.path);
+    Ok(PathBuf::from(String::from_utf8_lossy(&path).to_string()))
+}
+
+pub fn request(url: &Url) -> Result<HttpResponse, String> {
+    let path = to_file_path(url)?;
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut headers = HashMap::new();
+    headers.insert(
+        "content-type".to_string(),
+        guess_content_type(&url.path).to_string(),
+    );
+    headers.insert("content-length".to_string(), bytes.len().to_string());
+
+    Ok(HttpResponse {
+        version: "HTTP/1.0".to_string(),
+        status: 200,
+        explanation: "OK".to_string(),
+        headers,
+        body: bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("learn-browser-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_guess_content_type() {
+        assert_eq!(guess_content_type("/index.html"), "text/html");
+        assert_eq!(guess_content_type("/INDEX.HTM"), "text/html");
+        assert_eq!(guess_content_type("/README.md"), "text/markdown");
+        assert_eq!(guess_content_type("/style.css"), "text/css");
+        assert_eq!(
+            guess_content_type("/archive.tar.gz"),
+            "application/octet-stream"
+        );
+        assert_eq!(guess_content_type("/Makefile"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_to_file_path() {
+        let url = Url::new("file:///home/user/My%20Docs/page.html").unwrap();
+        assert_eq!(
+            to_file_path(&url).unwrap(),
+            PathBuf::from("/home/user/My Docs/page.html")
+        );
+
+        let url = Url::new("file://localhost/tmp/a.html").unwrap();
+        assert_eq!(to_file_path(&url).unwrap(), PathBuf::from("/tmp/a.html"));
+    }
+
+    #[test]
+    fn test_file_path_round_trip() {
+        let directory =
+            env::temp_dir().join(format!("learn-browser-{} scr a#b?c", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("x%41 ü.html");
+        fs::write(&path, "page").unwrap();
+
+        let url = Url::from_file_path(&path).unwrap();
+        assert_eq!(url.fragment, None);
+        assert_eq!(url.query, None);
+        assert_eq!(to_file_path(&url).unwrap(), path.canonicalize().unwrap());
+        assert_eq!(request(&url).unwrap().text(), "page");
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_to_file_path_remote_host() {
+        let url = Url::new("file://server/share/a.html").unwrap();
+        assert_eq!(
+            to_file_path(&url).unwrap_err(),
+            "Unsupported file URL host: server"
+        );
+    }
+
+    #[test]
+    fn test_file_request() {
+        let path = temp_file("page.html", "<html><body>Offline</body></html>");
+        let url = Url::new(&format!("file://{}", path.display())).unwrap();
+
+        let response = request(&url).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.headers.get("content-type"),
+            Some(&"text/html".to_string())
+        );
+        assert_eq!(
+            response.headers.get("content-length"),
+            Some(&"33".to_string())
+        );
+        assert_eq!(response.text(), "<html><body>Offline</body></html>");
+    }
+
+    #[test]
+    fn test_file_request_missing_file() {
+        let url = Url::new("file:///nonexistent/learn-browser/missing.html").unwrap();
+
+        let result = request(&url);
+
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .starts_with("Failed to read /nonexistent/learn-browser/missing.html")
+        );
+    }
+}
@@ -0,0 +1,431 @@
+use crate::socket::SocketReader;
+use crate::url::{HttpResponse, Socket, Url, escape_html, percent_encode_path};
+use std::collections::HashMap;
+
+pub const DEFAULT_PORT: u16 = 1965;
+
+pub(crate) fn make_request_with_socket<S: Socket>(
+    socket: &mut S,
+    url: &Url,
+) -> Result<HttpResponse, String> {
+    socket.connect(&url.host, url.port)?;
+
+    // A Gemini request is just the absolute URL on a single line
+    let gemini_request = format!("gemini://{}{}\r\n", url.authority(), url.request_target());
+    socket.send(gemini_request.as_bytes())?;
+    let mut reader = SocketReader::new(socket);
+
+    // The response header is "<two digit status> <meta>"
+    let header = reader.read_line()?;
+    let header = header.trim_end_matches("\r\n");
+    let (code, meta) = header.split_once(' ').unwrap_or((header, ""));
+
+    if code.len() != 2 {
+        return Err("Invalid Gemini status line".to_string());
+    }
+    let status = code
+        .parse::<u16>()
+        .map_err(|_| "Invalid Gemini status code".to_string())?;
+    let meta = meta.trim().to_string();
+
+    let mut headers = HashMap::new();
+    let body = match status / 10 {
+        2 => {
+            let content_type = if meta.is_empty() {
+                "text/gemini; charset=utf-8".to_string()
+            } else {
+                meta.clone()
+            };
+            headers.insert("content-type".to_string(), content_type);
+            reader.read_to_end()?
+        }
+        3 => {
+            headers.insert("location".to_string(), meta.clone());
+            Vec::new()
+        }
+        1 | 4 | 5 | 6 => Vec::new(),
+        _ => return Err(format!("Unknown Gemini status code: {}", status)),
+    };
+
+    Ok(HttpResponse {
+        version: "Gemini".to_string(),
+        status,
+        explanation: meta,
+        headers,
+        body,
+    })
+}
+
+// Clients are expected to give up after a handful of redirects
+const MAX_REDIRECTS: usize = 5;
+
+// Follows 3x redirects between gemini:// URLs. A redirect to another scheme
+// is returned as is, so the user sees where it leads before going there.
+pub(crate) fn follow_redirects<F>(url: &Url, mut request: F) -> Result<HttpResponse, String>
+where
+    F: FnMut(&Url) -> Result<HttpResponse, String>,
+{
+    let mut url = url.clone();
+    for _ in 0..=MAX_REDIRECTS {
+        let response = request(&url)?;
+        let Some(location) = response.headers.get("location") else {
+            return Ok(response);
+        };
+        let target = url.join(location)?;
+        if target.scheme != url.scheme {
+            return Ok(response);
+        }
+        url = target;
+    }
+    Err(format!("Too many redirects (more than {})", MAX_REDIRECTS))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputPrompt {
+    pub prompt: String,
+    // Status 11: the input is a password or similar and shouldn't be echoed
+    pub sensitive: bool,
+}
+
+// What a 1x response asks the user for. The answer is sent back as the
+// query of the same URL, see `url_with_input`.
+pub fn input_prompt(response: &HttpResponse) -> Option<InputPrompt> {
+    if response.version != "Gemini" || response.status / 10 != 1 {
+        return None;
+    }
+    Some(InputPrompt {
+        prompt: response.explanation.clone(),
+        sensitive: response.status == 11,
+    })
+}
+
+pub fn url_with_input(url: &Url, input: &str) -> Url {
+    let mut url = url.clone();
+    url.query = Some(percent_encode_path(input.as_bytes()));
+    url.fragment = None;
+    url
+}
+
+pub fn is_gemtext_content_type(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .eq_ignore_ascii_case("text/gemini")
+}
+
+pub fn gemtext_to_html(gemtext: &str) -> String {
+    let mut html = String::from("<html><body>\n");
+    let mut preformatted = false;
+    let mut in_list = false;
+
+    for line in gemtext.lines() {
+        if line.starts_with("```") {
+            if in_list {
+                html.push_str("</ul>\n");
+                in_list = false;
+            }
+            html.push_str(if preformatted { "</pre>\n" } else { "<pre>\n" });
+            preformatted = !preformatted;
+            continue;
+        }
+        if preformatted {
+            html.push_str(&escape_html(line));
+            html.push('\n');
+            continue;
+        }
+
+        let list_item = line.strip_prefix("* ");
+        if list_item.is_some() && !in_list {
+            html.push_str("<ul>\n");
+            in_list = true;
+        } else if list_item.is_none() && in_list {
+            html.push_str("</ul>\n");
+            in_list = false;
+        }
+
+        if let Some(item) = list_item {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(item.trim())));
+        } else if let Some(link) = line.strip_prefix("=>") {
+            let link = link.trim();
+            let (target, label) = match link.split_once(char::is_whitespace) {
+                Some((target, label)) => (target, label.trim()),
+                None => (link, link),
+            };
+            html.push_str(&format!(
+                "<p><a href=\"{}\">{}</a></p>\n",
+                escape_html(target),
+                escape_html(label)
+            ));
+        } else if let Some(heading) = line.strip_prefix("###") {
+            html.push_str(&format!("<h3>{}</h3>\n", escape_html(heading.trim())));
+        } else if let Some(heading) = line.strip_prefix("##") {
+            html.push_str(&format!("<h2>{}</h2>\n", escape_html(heading.trim())));
+        } else if let Some(heading) = line.strip_prefix('#') {
+            html.push_str(&format!("<h1>{}</h1>\n", escape_html(heading.trim())));
+        } else if let Some(quote) = line.strip_prefix('>') {
+            html.push_str(&format!(
+                "<blockquote>{}</blockquote>\n",
+                escape_html(quote.trim())
+            ));
+        } else if !line.trim().is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", escape_html(line)));
+        }
+    }
+
+    if in_list {
+        html.push_str("</ul>\n");
+    }
+    if preformatted {
+        html.push_str("</pre>\n");
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct TestSocket {
+        connect_calls: Vec<(String, u16)>,
+        send_calls: Vec<String>,
+        response: Vec<u8>,
+        position: usize,
+    }
+
+    impl TestSocket {
+        fn with_response_lines(lines: Vec<&str>) -> Self {
+            TestSocket {
+                connect_calls: Vec::new(),
+                send_calls: Vec::new(),
+                response: lines.concat().into_bytes(),
+                position: 0,
+            }
+        }
+    }
+
+    impl Socket for TestSocket {
+        fn connect(&mut self, host: &str, port: u16) -> Result<(), String> {
+            self.connect_calls.push((host.to_string(), port));
+            Ok(())
+        }
+
+        fn send(&mut self, data: &[u8]) -> Result<(), String> {
+            self.send_calls
+                .push(String::from_utf8_lossy(data).to_string());
+            Ok(())
+        }
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+            let remaining = &self.response[self.position..];
+            let count = remaining.len().min(buffer.len());
+            buffer[..count].copy_from_slice(&remaining[..count]);
+            self.position += count;
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_gemini_request_success() {
+        let mut socket = TestSocket::with_response_lines(vec![
+            "20 text/gemini\r\n",
+            "# Hello\n",
+            "Welcome to Gemini\n",
+        ]);
+        let url = Url::new("gemini://example.org/index.gmi").unwrap();
+
+        let response = make_request_with_socket(&mut socket, &url).unwrap();
+
+        assert_eq!(
+            socket.connect_calls,
+            vec![("example.org".to_string(), 1965)]
+        );
+        assert_eq!(
+            socket.send_calls,
+            vec!["gemini://example.org/index.gmi\r\n"]
+        );
+        assert_eq!(response.version, "Gemini");
+        assert_eq!(response.status, 20);
+        assert_eq!(
+            response.headers.get("content-type"),
+            Some(&"text/gemini".to_string())
+        );
+        assert_eq!(response.text(), "# Hello\nWelcome to Gemini\n");
+    }
+
+    #[test]
+    fn test_gemini_request_default_mime_type() {
+        let mut socket = TestSocket::with_response_lines(vec!["20\r\n", "text"]);
+        let url = Url::new("gemini://example.org/").unwrap();
+
+        let response = make_request_with_socket(&mut socket, &url).unwrap();
+
+        assert_eq!(
+            response.headers.get("content-type"),
+            Some(&"text/gemini; charset=utf-8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_gemini_request_with_query() {
+        let mut socket = TestSocket::with_response_lines(vec!["20 text/gemini\r\n"]);
+        let url = Url::new("gemini://example.org/search?rust#top").unwrap();
+
+        make_request_with_socket(&mut socket, &url).unwrap();
+
+        assert_eq!(
+            socket.send_calls,
+            vec!["gemini://example.org/search?rust\r\n"]
+        );
+    }
+
+    #[test]
+    fn test_gemini_request_redirect() {
+        let mut socket = TestSocket::with_response_lines(vec!["31 gemini://example.org/new\r\n"]);
+        let url = Url::new("gemini://example.org/old").unwrap();
+
+        let response = make_request_with_socket(&mut socket, &url).unwrap();
+
+        assert_eq!(response.status, 31);
+        assert_eq!(
+            response.headers.get("location"),
+            Some(&"gemini://example.org/new".to_string())
+        );
+        assert_eq!(response.text(), "");
+    }
+
+    fn gemini_response(header: &str) -> HttpResponse {
+        let mut socket = TestSocket::with_response_lines(vec![header]);
+        let url = Url::new("gemini://example.org/").unwrap();
+        make_request_with_socket(&mut socket, &url).unwrap()
+    }
+
+    #[test]
+    fn test_follow_redirects() {
+        let url = Url::new("gemini://example.org/a").unwrap();
+        let mut requested = Vec::new();
+        let response = follow_redirects(&url, |url| {
+            requested.push(url.to_string());
+            Ok(match url.path.as_str() {
+                "/a" => gemini_response("31 /b\r\n"),
+                "/b" => gemini_response("30 gemini://example.net/c\r\n"),
+                _ => gemini_response("20 text/plain\r\n"),
+            })
+        })
+        .unwrap();
+        assert_eq!(response.status, 20);
+        assert_eq!(
+            requested,
+            [
+                "gemini://example.org/a",
+                "gemini://example.org/b",
+                "gemini://example.net/c"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_follow_redirects_stops() {
+        let url = Url::new("gemini://example.org/").unwrap();
+        let response =
+            follow_redirects(&url, |_| Ok(gemini_response("31 https://example.org/\r\n"))).unwrap();
+        assert_eq!(response.status, 31);
+
+        let mut requests = 0;
+        let result = follow_redirects(&url, |_| {
+            requests += 1;
+            Ok(gemini_response("31 /loop\r\n"))
+        });
+        assert!(result.is_err());
+        assert_eq!(requests, MAX_REDIRECTS + 1);
+    }
+
+    #[test]
+    fn test_input_prompt() {
+        assert_eq!(
+            input_prompt(&gemini_response("10 Search terms\r\n")),
+            Some(InputPrompt {
+                prompt: "Search terms".to_string(),
+                sensitive: false,
+            })
+        );
+        assert!(
+            input_prompt(&gemini_response("11 Password\r\n"))
+                .unwrap()
+                .sensitive
+        );
+        assert_eq!(input_prompt(&gemini_response("20 text/plain\r\n")), None);
+
+        let url = Url::new("gemini://example.org/search?old#top").unwrap();
+        assert_eq!(
+            url_with_input(&url, "rust lang?").to_string(),
+            "gemini://example.org/search?rust%20lang%3F"
+        );
+    }
+
+    #[test]
+    fn test_gemini_request_failure_status() {
+        let mut socket = TestSocket::with_response_lines(vec!["51 Not found\r\n"]);
+        let url = Url::new("gemini://example.org/missing").unwrap();
+
+        let response = make_request_with_socket(&mut socket, &url).unwrap();
+
+        assert_eq!(response.status, 51);
+        assert_eq!(response.explanation, "Not found");
+        assert!(response.headers.is_empty());
+    }
+
+    #[test]
+    fn test_gemini_request_invalid_status() {
+        let mut socket = TestSocket::with_response_lines(vec!["200 OK\r\n"]);
+        let url = Url::new("gemini://example.org/").unwrap();
+
+        let result = make_request_with_socket(&mut socket, &url);
+
+        assert_eq!(result.unwrap_err(), "Invalid Gemini status line");
+    }
+
+    #[test]
+    fn test_is_gemtext_content_type() {
+        assert!(is_gemtext_content_type("text/gemini"));
+        assert!(is_gemtext_content_type("text/gemini; lang=en"));
+        assert!(!is_gemtext_content_type("text/plain"));
+    }
+
+    #[test]
+    fn test_gemtext_to_html() {
+        let gemtext = "# Title\n\
+                       Some text & more\n\
+                       => gemini://example.org/about About us\n\
+                       => /plain\n\
+                       * one\n\
+                       * two\n\
+                       > quoted\n\
+                       ```\n\
+                       # not a heading\n\
+                       ```\n";
+        assert_eq!(
+            gemtext_to_html(gemtext),
+            "<html><body>\n\
+             <h1>Title</h1>\n\
+             <p>Some text &amp; more</p>\n\
+             <p><a href=\"gemini://example.org/about\">About us</a></p>\n\
+             <p><a href=\"/plain\">/plain</a></p>\n\
+             <ul>\n<li>one</li>\n<li>two</li>\n</ul>\n\
+             <blockquote>quoted</blockquote>\n\
+             <pre>\n# not a heading\n</pre>\n\
+             </body></html>\n"
+        );
+    }
+
+    #[test]
+    fn test_gemtext_list_closed_before_preformatted() {
+        assert_eq!(
+            gemtext_to_html("* a\n```\ncode\n```\n"),
+            "<html><body>\n<ul>\n<li>a</li>\n</ul>\n<pre>\ncode\n</pre>\n</body></html>\n"
+        );
+    }
+}
@@ -1,2 +1,16 @@
 pub mod url;
-pub mod socket;
\ No newline at end of file
+pub mod socket;
+pub mod feed;
+pub mod markdown;
+pub mod gemini;
+pub mod scheme;
+pub mod public_suffix;
+pub mod file;
+pub mod data;
+pub mod about;
+pub mod punycode;
+pub mod cache;
+pub mod content_encoding;
+pub mod charset;
+pub mod sniff;
+pub mod content_type;
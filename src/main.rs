@@ -1,5 +1,19 @@
 pub mod url;
 pub mod socket;
+pub mod feed;
+pub mod markdown;
+pub mod gemini;
+pub mod scheme;
+pub mod public_suffix;
+pub mod file;
+pub mod data;
+pub mod about;
+pub mod punycode;
+pub mod cache;
+pub mod content_encoding;
+pub mod charset;
+pub mod sniff;
+pub mod content_type;
 
 fn main() {
     println!("Hello, world!");
@@ -0,0 +1,59 @@
+use pulldown_cmark::{Options, Parser, html};
+
+pub fn is_markdown_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase();
+    mime == "text/markdown" || mime == "text/x-markdown"
+}
+
+pub fn is_markdown_path(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".md") || path.ends_with(".markdown")
+}
+
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let parser = Parser::new_ext(markdown, options);
+    let mut body = String::new();
+    html::push_html(&mut body, parser);
+
+    format!("<html><body>\n{}</body></html>\n", body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_markdown_content_type() {
+        assert!(is_markdown_content_type("text/markdown"));
+        assert!(is_markdown_content_type("text/markdown; charset=UTF-8"));
+        assert!(is_markdown_content_type("text/x-markdown"));
+        assert!(!is_markdown_content_type("text/plain"));
+    }
+
+    #[test]
+    fn test_is_markdown_path() {
+        assert!(is_markdown_path("/docs/README.md"));
+        assert!(is_markdown_path("/notes.markdown"));
+        assert!(!is_markdown_path("/index.html"));
+        assert!(!is_markdown_path("/"));
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html =
+            markdown_to_html("# Title\n\nSome *emphasis* and a [link](http://example.com).\n");
+        assert_eq!(
+            html,
+            "<html><body>\n<h1>Title</h1>\n<p>Some <em>emphasis</em> and a <a href=\"http://example.com\">link</a>.</p>\n</body></html>\n"
+        );
+    }
+}
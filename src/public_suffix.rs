@@ -0,0 +1,83 @@
+use std::net::IpAddr;
+
+fn is_ip_address(host: &str) -> bool {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .is_ok()
+}
+
+// The public suffix plus one label, e.g. "example.co.uk" for "www.example.co.uk".
+// Returns None for IP addresses and for hosts that are themselves public suffixes.
+pub fn registrable_domain(host: &str) -> Option<&str> {
+    if is_ip_address(host) {
+        return None;
+    }
+    psl::domain_str(host)
+}
+
+pub fn is_public_suffix(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.');
+    !is_ip_address(domain) && psl::suffix_str(domain) == Some(domain)
+}
+
+// Checks a Set-Cookie Domain attribute against the host that sent it: the
+// host must domain-match it, and it must not be a public suffix such as
+// "co.uk" unless the host is exactly that domain.
+pub fn is_valid_cookie_domain(host: &str, domain: &str) -> bool {
+    let host = host.to_lowercase();
+    let domain = domain.trim_start_matches('.').to_lowercase();
+
+    if domain.is_empty() {
+        return false;
+    }
+    if host == domain {
+        return true;
+    }
+    if is_ip_address(&host) || !host.ends_with(&format!(".{}", domain)) {
+        return false;
+    }
+    !is_public_suffix(&domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("www.example.com"), Some("example.com"));
+        assert_eq!(
+            registrable_domain("a.b.example.co.uk"),
+            Some("example.co.uk")
+        );
+        assert_eq!(registrable_domain("user.github.io"), Some("user.github.io"));
+        assert_eq!(registrable_domain("co.uk"), None);
+        assert_eq!(registrable_domain("com"), None);
+        assert_eq!(registrable_domain("192.168.0.1"), None);
+    }
+
+    #[test]
+    fn test_is_public_suffix() {
+        assert!(is_public_suffix("com"));
+        assert!(is_public_suffix("co.uk"));
+        assert!(is_public_suffix("github.io"));
+        assert!(!is_public_suffix("example.com"));
+        assert!(!is_public_suffix("127.0.0.1"));
+    }
+
+    #[test]
+    fn test_is_valid_cookie_domain() {
+        assert!(is_valid_cookie_domain("www.example.co.uk", "example.co.uk"));
+        assert!(is_valid_cookie_domain(
+            "www.example.co.uk",
+            ".example.co.uk"
+        ));
+        assert!(is_valid_cookie_domain("example.com", "example.com"));
+        assert!(!is_valid_cookie_domain("www.example.co.uk", "co.uk"));
+        assert!(!is_valid_cookie_domain("www.example.com", "com"));
+        assert!(!is_valid_cookie_domain("www.example.com", "other.com"));
+        assert!(!is_valid_cookie_domain("notexample.com", "example.com"));
+        assert!(!is_valid_cookie_domain("192.168.0.1", "168.0.1"));
+    }
+}
@@ -0,0 +1,385 @@
+// Punycode (RFC 3492) and the ACE "xn--" label form used for
+// internationalized domain names.
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+const ACE_PREFIX: &str = "xn--";
+
+// Converts each non-ASCII label to its "xn--" form so the host can be
+// resolved and sent on the wire. ASCII labels are left untouched.
+pub fn domain_to_ascii(domain: &str) -> Result<String, String> {
+    let labels = domain
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                Ok(label.to_string())
+            } else {
+                let label: Vec<char> = label.to_lowercase().chars().collect();
+                encode(&label)
+                    .map(|encoded| format!("{}{}", ACE_PREFIX, encoded))
+                    .ok_or_else(|| format!("Invalid URL: bad host: {}", domain))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(labels.join("."))
+}
+
+// Converts "xn--" labels back to Unicode for display. Labels that don't
+// decode are shown as they are.
+pub fn domain_to_unicode(domain: &str) -> String {
+    domain
+        .split('.')
+        .map(|label| {
+            match label
+                .get(..ACE_PREFIX.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(ACE_PREFIX))
+                .and_then(|_| decode(&label[ACE_PREFIX.len()..]))
+            {
+                Some(decoded) => decoded,
+                None => label.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+// Like domain_to_unicode, but a label is only decoded when it can't pass for
+// a different name, as browsers do in the address bar: "xn--80ak6aa92e.com"
+// is Cyrillic "аррӏе.com" and stays in its ACE form.
+pub fn domain_to_display(domain: &str) -> String {
+    let tld = domain_to_unicode(
+        domain
+            .trim_end_matches('.')
+            .rsplit('.')
+            .next()
+            .unwrap_or(""),
+    );
+    domain
+        .split('.')
+        .map(|label| {
+            let decoded = domain_to_unicode(label);
+            if decoded == label || is_safe_to_display(&decoded, &tld) {
+                decoded
+            } else {
+                label.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Script {
+    // Digits, hyphens and combining marks, which go with any script
+    Common,
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Thai,
+    Georgian,
+    Hangul,
+    Hiragana,
+    Katakana,
+    Bopomofo,
+    Han,
+    // Anything not listed above is never decoded
+    Unknown,
+}
+
+fn script(c: char) -> Script {
+    match c as u32 {
+        0x2d | 0x30..=0x39 | 0x300..=0x36f | 0x30fc => Script::Common,
+        0x41..=0x5a | 0x61..=0x7a | 0xc0..=0xd6 | 0xd8..=0xf6 | 0xf8..=0x24f => Script::Latin,
+        0x1e00..=0x1eff => Script::Latin,
+        0x370..=0x3ff | 0x1f00..=0x1fff => Script::Greek,
+        0x400..=0x52f => Script::Cyrillic,
+        0x530..=0x58f => Script::Armenian,
+        0x590..=0x5ff => Script::Hebrew,
+        0x600..=0x6ff | 0x750..=0x77f => Script::Arabic,
+        0x900..=0x97f => Script::Devanagari,
+        0x980..=0x9ff => Script::Bengali,
+        0xe00..=0xe7f => Script::Thai,
+        0x10a0..=0x10ff => Script::Georgian,
+        0x1100..=0x11ff | 0x3130..=0x318f | 0xac00..=0xd7af => Script::Hangul,
+        0x3040..=0x309f => Script::Hiragana,
+        0x30a0..=0x30ff => Script::Katakana,
+        0x3100..=0x312f => Script::Bopomofo,
+        0x3400..=0x4dbf | 0x4e00..=0x9fff => Script::Han,
+        _ => Script::Unknown,
+    }
+}
+
+// Scripts that are written together, so mixing them isn't suspicious
+const SCRIPT_MIXES: [&[Script]; 3] = [
+    &[
+        Script::Latin,
+        Script::Hiragana,
+        Script::Katakana,
+        Script::Han,
+    ],
+    &[Script::Latin, Script::Bopomofo, Script::Han],
+    &[Script::Latin, Script::Hangul, Script::Han],
+];
+
+// Letters that look like Latin ones, so a label made only of them can
+// imitate an ASCII name (Unicode's whole-script confusables)
+const CYRILLIC_LOOKALIKES: &str = "аысԁеԍһіюјӏорԗԛѕԝхуъьҽпгѵѡ";
+const GREEK_LOOKALIKES: &str = "αικνορτυχ";
+
+// A decoded label is shown as Unicode when it sticks to one script (or a
+// usual mix such as Japanese kana and kanji) and isn't entirely made of
+// Latin lookalikes, unless the TLD is in that script too, as in ".рф"
+fn is_safe_to_display(label: &str, tld: &str) -> bool {
+    let mut scripts: Vec<Script> = label
+        .chars()
+        .map(script)
+        .filter(|&script| script != Script::Common)
+        .collect();
+    scripts.sort();
+    scripts.dedup();
+
+    if scripts.contains(&Script::Unknown) {
+        return false;
+    }
+    match scripts.as_slice() {
+        [] => true,
+        [single] => {
+            let lookalikes = match single {
+                Script::Cyrillic => CYRILLIC_LOOKALIKES,
+                Script::Greek => GREEK_LOOKALIKES,
+                _ => return true,
+            };
+            let imitates_latin = label
+                .chars()
+                .all(|c| script(c) == Script::Common || lookalikes.contains(c));
+            !imitates_latin || tld.chars().any(|c| script(c) == *single)
+        }
+        mixed => SCRIPT_MIXES
+            .iter()
+            .any(|allowed| mixed.iter().all(|script| allowed.contains(script))),
+    }
+}
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(digit: u32) -> char {
+    match digit {
+        0..=25 => (b'a' + digit as u8) as char,
+        _ => (b'0' + (digit - 26) as u8) as char,
+    }
+}
+
+fn decode_digit(byte: u8) -> Option<u32> {
+    match byte {
+        b'a'..=b'z' => Some((byte - b'a') as u32),
+        b'A'..=b'Z' => Some((byte - b'A') as u32),
+        b'0'..=b'9' => Some((byte - b'0') as u32 + 26),
+        _ => None,
+    }
+}
+
+fn encode(input: &[char]) -> Option<String> {
+    let mut output: String = input.iter().filter(|c| c.is_ascii()).collect();
+    let basic_count = output.len() as u32;
+    if basic_count > 0 {
+        output.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic_count;
+
+    while (handled as usize) < input.len() {
+        let m = input.iter().map(|&c| c as u32).filter(|&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in input {
+            let c = c as u32;
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic_count);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(output)
+}
+
+fn decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(pos) => (&input[..pos], &input[pos + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut bytes = extended.bytes().peekable();
+
+    while bytes.peek().is_some() {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = decode_digit(bytes.next()?)?;
+            i = i.checked_add(digit.checked_mul(w)?)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode() {
+        let cases = [
+            ("日本語", "wgv71a119e"),
+            ("bücher", "bcher-kva"),
+            ("münchen", "mnchen-3ya"),
+            ("παράδειγμα", "hxajbheg2az3al"),
+        ];
+        for (unicode, ace) in cases {
+            let chars: Vec<char> = unicode.chars().collect();
+            assert_eq!(encode(&chars).as_deref(), Some(ace), "encode {}", unicode);
+            assert_eq!(decode(ace).as_deref(), Some(unicode), "decode {}", ace);
+        }
+    }
+
+    #[test]
+    fn test_domain_to_ascii() {
+        assert_eq!(
+            domain_to_ascii("日本語.example").unwrap(),
+            "xn--wgv71a119e.example"
+        );
+        assert_eq!(
+            domain_to_ascii("www.Bücher.de").unwrap(),
+            "www.xn--bcher-kva.de"
+        );
+        assert_eq!(domain_to_ascii("example.com").unwrap(), "example.com");
+    }
+
+    #[test]
+    fn test_domain_to_unicode() {
+        assert_eq!(
+            domain_to_unicode("xn--wgv71a119e.example"),
+            "日本語.example"
+        );
+        assert_eq!(domain_to_unicode("www.XN--bcher-kva.de"), "www.bücher.de");
+        assert_eq!(domain_to_unicode("example.com"), "example.com");
+        // Not valid punycode, so it is displayed unchanged
+        assert_eq!(domain_to_unicode("xn--a!b.example"), "xn--a!b.example");
+    }
+
+    fn display(unicode: &str) -> String {
+        domain_to_display(&domain_to_ascii(unicode).unwrap())
+    }
+
+    #[test]
+    fn test_domain_to_display_single_script() {
+        assert_eq!(display("www.bücher.de"), "www.bücher.de");
+        assert_eq!(display("日本語.example"), "日本語.example");
+        assert_eq!(display("пример.рф"), "пример.рф");
+        assert_eq!(display("παράδειγμα.gr"), "παράδειγμα.gr");
+        // Japanese mixes kanji, kana and Latin letters
+        assert_eq!(display("東京タワーtv.jp"), "東京タワーtv.jp");
+    }
+
+    #[test]
+    fn test_domain_to_display_mixed_scripts() {
+        // Cyrillic "а" followed by Latin "pple"
+        assert_eq!(display("аpple.com"), "xn--pple-43d.com");
+        // Greek "ο" in a Latin word
+        assert_eq!(
+            display("gοοgle.com"),
+            domain_to_ascii("gοοgle.com").unwrap()
+        );
+        assert_eq!(display("ab한국.com"), "ab한국.com");
+        assert_eq!(display("aπ中.com"), domain_to_ascii("aπ中.com").unwrap());
+    }
+
+    #[test]
+    fn test_domain_to_display_whole_script_confusables() {
+        // All Cyrillic, but every letter looks Latin: "аррӏе" reads as "apple"
+        assert_eq!(
+            domain_to_display("xn--80ak6aa92e.com"),
+            "xn--80ak6aa92e.com"
+        );
+        assert_eq!(display("www.аррӏе.com"), "www.xn--80ak6aa92e.com");
+        assert_eq!(display("οκ.com"), domain_to_ascii("οκ.com").unwrap());
+        // Under a Cyrillic TLD there is no Latin name to imitate
+        assert_eq!(display("аррӏе.рф"), "аррӏе.рф");
+    }
+
+    #[test]
+    fn test_domain_to_display_unknown_script() {
+        // Cherokee isn't in the script table, so it is never decoded
+        assert_eq!(display("ᎠᎡᎢ.com"), domain_to_ascii("ᎠᎡᎢ.com").unwrap());
+    }
+}
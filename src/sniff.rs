@@ -0,0 +1,228 @@
+use crate::content_type::mime_essence;
+
+// A subset of the WHATWG MIME Sniffing Standard (https://mimesniff.spec.whatwg.org/):
+// responses without a usable Content-Type are classified from their first
+// bytes, and text/plain is checked for binary data. A declared type is never
+// upgraded to HTML, so a text file can't turn into a page.
+
+// Only the start of the body is inspected, as in the standard
+const SNIFF_LENGTH: usize = 1445;
+
+// Tags that mark a document as HTML when they open the body, followed by a
+// space or ">"
+const HTML_TAGS: [&[u8]; 17] = [
+    b"<!DOCTYPE HTML",
+    b"<HTML",
+    b"<HEAD",
+    b"<SCRIPT",
+    b"<IFRAME",
+    b"<H1",
+    b"<DIV",
+    b"<FONT",
+    b"<TABLE",
+    b"<A",
+    b"<STYLE",
+    b"<TITLE",
+    b"<B",
+    b"<BODY",
+    b"<BR",
+    b"<P",
+    b"<!--",
+];
+
+const IMAGE_SIGNATURES: [(&[u8], &str); 7] = [
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"BM", "image/bmp"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+    (b"\x00\x00\x02\x00", "image/x-icon"),
+];
+
+const BYTE_ORDER_MARKS: [&[u8]; 3] = [b"\xef\xbb\xbf", b"\xfe\xff", b"\xff\xfe"];
+
+fn is_unknown(essence: &str) -> bool {
+    matches!(
+        essence,
+        "" | "unknown/unknown" | "application/unknown" | "*/*"
+    ) || !essence.contains('/')
+}
+
+// Control bytes that never appear in text, per the standard's "binary data byte"
+fn is_binary_byte(byte: u8) -> bool {
+    matches!(byte, 0x00..=0x08 | 0x0b | 0x0e..=0x1a | 0x1c..=0x1f)
+}
+
+fn starts_with_ignore_case(data: &[u8], prefix: &[u8]) -> bool {
+    data.len() >= prefix.len() && data[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+fn sniff_html(data: &[u8]) -> bool {
+    let start = data
+        .iter()
+        .position(|byte| !matches!(byte, b'\t' | b'\n' | 0x0c | b'\r' | b' '))
+        .unwrap_or(data.len());
+    let data = &data[start..];
+    HTML_TAGS.iter().any(|tag| {
+        starts_with_ignore_case(data, tag) && matches!(data.get(tag.len()), Some(b' ' | b'>'))
+    })
+}
+
+fn sniff_image(data: &[u8]) -> Option<&'static str> {
+    if data.len() >= 14 && data.starts_with(b"RIFF") && &data[8..14] == b"WEBPVP" {
+        return Some("image/webp");
+    }
+    IMAGE_SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+        .map(|&(_, mime)| mime)
+}
+
+fn has_byte_order_mark(data: &[u8]) -> bool {
+    BYTE_ORDER_MARKS.iter().any(|mark| data.starts_with(mark))
+}
+
+fn text_or_binary(data: &[u8]) -> &'static str {
+    if has_byte_order_mark(data) || !data.iter().copied().any(is_binary_byte) {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+fn sniff_unknown(data: &[u8]) -> &'static str {
+    if sniff_html(data) {
+        "text/html"
+    } else if starts_with_ignore_case(data, b"<?xml") {
+        "text/xml"
+    } else if data.starts_with(b"%PDF-") {
+        "application/pdf"
+    } else if let Some(image) = sniff_image(data) {
+        image
+    } else {
+        text_or_binary(data)
+    }
+}
+
+// The Content-Type to handle a response as. `declared` is the header as
+// sent and `no_sniff` is set by "X-Content-Type-Options: nosniff", which
+// keeps any declared type as is.
+pub fn sniff_content_type(declared: Option<&str>, no_sniff: bool, body: &[u8]) -> String {
+    let data = &body[..body.len().min(SNIFF_LENGTH)];
+    let declared_essence = declared.map(mime_essence).unwrap_or_default();
+
+    if is_unknown(&declared_essence) {
+        return sniff_unknown(data).to_string();
+    }
+    let declared = declared.unwrap_or_default().to_string();
+    if no_sniff || declared_essence != "text/plain" {
+        return declared;
+    }
+    match sniff_image(data) {
+        Some(image) => image.to_string(),
+        None if text_or_binary(data) == "text/plain" => declared,
+        None => "application/octet-stream".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR";
+
+    #[test]
+    fn test_sniff_missing_content_type() {
+        assert_eq!(
+            sniff_content_type(None, false, b"  <!doctype html><p>hi"),
+            "text/html"
+        );
+        assert_eq!(sniff_content_type(None, false, b"<p>hi</p>"), "text/html");
+        assert_eq!(
+            sniff_content_type(None, false, b"<?xml version"),
+            "text/xml"
+        );
+        assert_eq!(
+            sniff_content_type(None, false, b"%PDF-1.7"),
+            "application/pdf"
+        );
+        assert_eq!(sniff_content_type(None, false, PNG), "image/png");
+        assert_eq!(
+            sniff_content_type(None, false, b"GIF89a\x01\x00"),
+            "image/gif"
+        );
+        assert_eq!(
+            sniff_content_type(None, false, b"RIFF\x24\x00\x00\x00WEBPVP8 "),
+            "image/webp"
+        );
+        assert_eq!(
+            sniff_content_type(None, false, b"plain words"),
+            "text/plain"
+        );
+        assert_eq!(
+            sniff_content_type(None, false, b"\x00\x01\x02"),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_sniff_html_needs_tag_end() {
+        // "<Bogus" starts like "<B" but isn't that tag
+        assert_eq!(sniff_content_type(None, false, b"<Bogus>"), "text/plain");
+        assert_eq!(sniff_content_type(None, false, b"<b>bold</b>"), "text/html");
+    }
+
+    #[test]
+    fn test_sniff_unknown_declared_types() {
+        assert_eq!(
+            sniff_content_type(Some("*/*"), false, b"<html>"),
+            "text/html"
+        );
+        assert_eq!(
+            sniff_content_type(Some("unknown/unknown"), false, PNG),
+            "image/png"
+        );
+        // Sniffing unknown types happens even with nosniff
+        assert_eq!(sniff_content_type(Some(""), true, PNG), "image/png");
+    }
+
+    #[test]
+    fn test_sniff_text_plain() {
+        let declared = Some("text/plain; charset=utf-8");
+        assert_eq!(
+            sniff_content_type(declared, false, b"hello"),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(sniff_content_type(declared, false, PNG), "image/png");
+        assert_eq!(
+            sniff_content_type(declared, false, b"\x00\x00\x00binary"),
+            "application/octet-stream"
+        );
+        // text/plain is never upgraded to HTML
+        assert_eq!(
+            sniff_content_type(declared, false, b"<html><body>"),
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn test_nosniff_keeps_declared_type() {
+        assert_eq!(
+            sniff_content_type(Some("text/plain"), true, PNG),
+            "text/plain"
+        );
+    }
+
+    #[test]
+    fn test_declared_types_are_kept() {
+        assert_eq!(
+            sniff_content_type(Some("text/html"), false, PNG),
+            "text/html"
+        );
+        assert_eq!(
+            sniff_content_type(Some("image/jpeg"), false, b"<html>"),
+            "image/jpeg"
+        );
+    }
+}
@@ -1,7 +1,88 @@
 use crate::url::Socket;
 use native_tls::TlsConnector;
+use std::collections::HashMap;
+use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Certificates seen on the first visit to each Gemini host, keyed by "host:port".
+static KNOWN_GEMINI_HOSTS: LazyLock<Mutex<KnownHosts>> =
+    LazyLock::new(|| Mutex::new(KnownHosts::new()));
+
+// Simulated network conditions applied to every new connection while set.
+static THROTTLE: Mutex<Option<Throttle>> = Mutex::new(None);
+
+// Where to record the bytes of every new connection while set.
+static WIRE_LOG: Mutex<Option<WireLog>> = Mutex::new(None);
+
+// Numbers connections so each gets its own transcript file
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+// Headers whose values are replaced in transcripts unless redaction is off
+const REDACTED_HEADERS: [&str; 3] = ["authorization", "proxy-authorization", "cookie"];
+
+// Idle keep-alive connections shared by the HTTP and HTTPS handlers.
+pub(crate) static CONNECTION_POOL: LazyLock<ConnectionPool> =
+    LazyLock::new(|| ConnectionPool::new(IDLE_TIMEOUT));
+
+// Servers commonly close idle connections after 5-60 seconds
+const IDLE_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_IDLE_PER_HOST: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Throttle {
+    pub bytes_per_second: Option<u64>,
+    pub latency: Duration,
+}
+
+pub fn set_throttle(throttle: Option<Throttle>) {
+    if let Ok(mut current) = THROTTLE.lock() {
+        *current = throttle;
+    }
+}
+
+pub fn current_throttle() -> Option<Throttle> {
+    THROTTLE.lock().ok().and_then(|current| *current)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WireLog {
+    pub directory: PathBuf,
+    // Replace Authorization and Cookie values sent to the server
+    pub redact: bool,
+}
+
+impl WireLog {
+    pub fn new(directory: PathBuf) -> Self {
+        WireLog {
+            directory,
+            redact: true,
+        }
+    }
+}
+
+pub fn set_wire_log(wire_log: Option<WireLog>) {
+    if let Ok(mut current) = WIRE_LOG.lock() {
+        *current = wire_log;
+    }
+}
+
+pub fn current_wire_log() -> Option<WireLog> {
+    WIRE_LOG.lock().ok().and_then(|current| current.clone())
+}
+
+// Applies the current throttle and wire log settings to a freshly opened connection
+pub(crate) fn instrument<S: Socket>(socket: S) -> ThrottledSocket<WireLogSocket<S>> {
+    ThrottledSocket::new(
+        WireLogSocket::new(socket, current_wire_log()),
+        current_throttle(),
+    )
+}
 
 #[derive(Debug)]
 pub struct HttpSocket<S: Read + Write> {
@@ -33,6 +114,136 @@ pub fn connect_https(
     Ok(HttpSocket { stream: tls_stream })
 }
 
+pub fn connect_gemini(
+    host: &str,
+    port: u16,
+) -> Result<HttpSocket<native_tls::TlsStream<TcpStream>>, String> {
+    let address = format!("{}:{}", host, port);
+    let tcp_stream = TcpStream::connect(&address)
+        .map_err(|e| format!("Failed to connect to {}: {}", address, e))?;
+
+    // Gemini servers commonly use self-signed certificates, so trust is
+    // established on first use instead of through a certificate authority.
+    let connector = TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()
+        .map_err(|e| format!("Failed to create TLS connector: {}", e))?;
+
+    let tls_stream = connector
+        .connect(host, tcp_stream)
+        .map_err(|e| format!("Failed to establish TLS connection: {}", e))?;
+
+    let certificate = tls_stream
+        .peer_certificate()
+        .map_err(|e| format!("Failed to read server certificate: {}", e))?
+        .ok_or_else(|| "Server did not present a certificate".to_string())?
+        .to_der()
+        .map_err(|e| format!("Failed to encode server certificate: {}", e))?;
+
+    KNOWN_GEMINI_HOSTS
+        .lock()
+        .map_err(|_| "Known hosts store is poisoned".to_string())?
+        .check(&address, &certificate)?;
+
+    Ok(HttpSocket { stream: tls_stream })
+}
+
+// Keeps the Gemini certificates in `path` so a certificate that changes
+// between runs is noticed, not just one that changes within a run
+pub fn set_known_hosts_file(path: &Path) -> Result<(), String> {
+    KNOWN_GEMINI_HOSTS
+        .lock()
+        .map_err(|_| "Known hosts store is poisoned".to_string())?
+        .set_file(path)
+}
+
+// The certificate pinned for each Gemini host, optionally mirrored to a file
+// with one "host:port<TAB>hex DER" line per host
+#[derive(Debug, Default)]
+pub struct KnownHosts {
+    certificates: HashMap<String, Vec<u8>>,
+    file: Option<PathBuf>,
+}
+
+impl KnownHosts {
+    pub fn new() -> Self {
+        KnownHosts::default()
+    }
+
+    // Loads the hosts pinned by earlier runs and saves every new one there.
+    // A stored pin is older than one seen in this run, so it wins.
+    pub fn set_file(&mut self, path: &Path) -> Result<(), String> {
+        let stored = match fs::read_to_string(path) {
+            Ok(stored) => stored,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        for line in stored.lines() {
+            if let Some((address, certificate)) = line.split_once('\t')
+                && let Some(certificate) = decode_hex(certificate)
+            {
+                self.certificates.insert(address.to_string(), certificate);
+            }
+        }
+        self.file = Some(path.to_path_buf());
+        self.save()
+    }
+
+    // Pins the certificate on the first visit to `address` and rejects any
+    // other certificate from then on
+    pub fn check(&mut self, address: &str, certificate: &[u8]) -> Result<(), String> {
+        match self.certificates.get(address) {
+            Some(known) if known != certificate => Err(format!(
+                "Certificate for {} changed since the first visit",
+                address
+            )),
+            Some(_) => Ok(()),
+            None => {
+                self.certificates
+                    .insert(address.to_string(), certificate.to_vec());
+                self.save()
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.file else {
+            return Ok(());
+        };
+        let mut lines: Vec<String> = self
+            .certificates
+            .iter()
+            .map(|(address, certificate)| format!("{}\t{}\n", address, encode_hex(certificate)))
+            .collect();
+        lines.sort();
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)
+                .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+        }
+        // Written aside and renamed so a crash never loses the pins
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, lines.concat())
+            .and_then(|_| fs::rename(&temporary, path))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 impl<S: Read + Write> Socket for HttpSocket<S> {
     fn connect(&mut self, _host: &str, _port: u16) -> Result<(), String> {
         // Connection is handled by the connect_http/connect_https functions
@@ -44,38 +255,685 @@ impl<S: Read + Write> Socket for HttpSocket<S> {
             .map_err(|e| format!("Failed to send data: {}", e))
     }
 
-    fn read_line(&mut self) -> Result<String, String> {
-        let mut line = String::new();
-        let mut buffer = [0; 1];
-        
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        self.stream
+            .read(buffer)
+            .map_err(|e| format!("Failed to read data: {}", e))
+    }
+}
+
+pub type PooledSocket = Box<dyn Socket + Send>;
+
+type PoolKey = (String, String, u16);
+
+// Open connections waiting to be reused, keyed by (scheme, host, port)
+pub struct ConnectionPool {
+    idle: Mutex<HashMap<PoolKey, Vec<(PooledSocket, Instant)>>>,
+    idle_timeout: Duration,
+}
+
+impl ConnectionPool {
+    pub fn new(idle_timeout: Duration) -> Self {
+        ConnectionPool {
+            idle: Mutex::new(HashMap::new()),
+            idle_timeout,
+        }
+    }
+
+    // The most recently returned connection that hasn't idled out
+    pub fn take(&self, scheme: &str, host: &str, port: u16) -> Option<PooledSocket> {
+        self.take_at(scheme, host, port, Instant::now())
+    }
+
+    pub fn put(&self, scheme: &str, host: &str, port: u16, socket: PooledSocket) {
+        self.put_at(scheme, host, port, socket, Instant::now());
+    }
+
+    pub fn idle_count(&self, scheme: &str, host: &str, port: u16) -> usize {
+        let key = (scheme.to_string(), host.to_string(), port);
+        self.idle
+            .lock()
+            .map(|idle| idle.get(&key).map_or(0, Vec::len))
+            .unwrap_or(0)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.clear();
+        }
+    }
+
+    fn take_at(&self, scheme: &str, host: &str, port: u16, now: Instant) -> Option<PooledSocket> {
+        let mut idle = self.idle.lock().ok()?;
+        let key = (scheme.to_string(), host.to_string(), port);
+        let sockets = idle.get_mut(&key)?;
+        sockets.retain(|(_, since)| now.duration_since(*since) < self.idle_timeout);
+        let socket = sockets.pop().map(|(socket, _)| socket);
+        if sockets.is_empty() {
+            idle.remove(&key);
+        }
+        socket
+    }
+
+    fn put_at(&self, scheme: &str, host: &str, port: u16, socket: PooledSocket, now: Instant) {
+        if let Ok(mut idle) = self.idle.lock() {
+            let sockets = idle
+                .entry((scheme.to_string(), host.to_string(), port))
+                .or_default();
+            sockets.push((socket, now));
+            if sockets.len() > MAX_IDLE_PER_HOST {
+                sockets.remove(0);
+            }
+        }
+    }
+}
+
+// Wraps a socket to delay it like a slow link: each connect and each
+// request/response round trip costs `latency`, and reads are paced to
+// `bytes_per_second`. Without a throttle it passes everything through.
+pub struct ThrottledSocket<S: Socket> {
+    socket: S,
+    throttle: Option<Throttle>,
+    // A reused keep-alive connection doesn't pay the connect latency again
+    connected: bool,
+    awaiting_response: bool,
+    read_started: Option<Instant>,
+    bytes_read: u64,
+}
+
+impl<S: Socket> ThrottledSocket<S> {
+    pub fn new(socket: S, throttle: Option<Throttle>) -> Self {
+        ThrottledSocket {
+            socket,
+            throttle,
+            connected: false,
+            awaiting_response: false,
+            read_started: None,
+            bytes_read: 0,
+        }
+    }
+}
+
+impl<S: Socket> Socket for ThrottledSocket<S> {
+    fn connect(&mut self, host: &str, port: u16) -> Result<(), String> {
+        if let Some(throttle) = self.throttle
+            && !self.connected
+        {
+            thread::sleep(throttle.latency);
+        }
+        self.connected = true;
+        self.socket.connect(host, port)
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        self.awaiting_response = true;
+        self.socket.send(data)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        let Some(throttle) = self.throttle else {
+            return self.socket.read(buffer);
+        };
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        if self.awaiting_response {
+            thread::sleep(throttle.latency);
+            self.awaiting_response = false;
+        }
+
+        let Some(bytes_per_second) = throttle.bytes_per_second.filter(|&rate| rate > 0) else {
+            return self.socket.read(buffer);
+        };
+
+        // Read in slices of a tenth of a second's worth of data so the
+        // pacing stays smooth instead of bursting a whole buffer at once
+        let slice = (bytes_per_second / 10).clamp(1, buffer.len() as u64) as usize;
+        let count = self.socket.read(&mut buffer[..slice])?;
+
+        let started = *self.read_started.get_or_insert_with(Instant::now);
+        self.bytes_read += count as u64;
+        let expected = Duration::from_secs_f64(self.bytes_read as f64 / bytes_per_second as f64);
+        let elapsed = started.elapsed();
+        if expected > elapsed {
+            thread::sleep(expected - elapsed);
+        }
+
+        Ok(count)
+    }
+}
+
+// Records everything sent and received on one connection to a text
+// transcript. Sits above TLS, so HTTPS traffic is logged as plaintext.
+// Failing to write the transcript never fails the connection.
+pub struct WireLogSocket<S: Socket> {
+    socket: S,
+    wire_log: Option<WireLog>,
+    transcript: Option<File>,
+    opened: Instant,
+}
+
+impl<S: Socket> WireLogSocket<S> {
+    pub fn new(socket: S, wire_log: Option<WireLog>) -> Self {
+        WireLogSocket {
+            socket,
+            wire_log,
+            transcript: None,
+            opened: Instant::now(),
+        }
+    }
+
+    fn open_transcript(&mut self, host: &str, port: u16) {
+        let Some(wire_log) = &self.wire_log else {
+            return;
+        };
+        if self.transcript.is_some() || fs::create_dir_all(&wire_log.directory).is_err() {
+            return;
+        }
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{}-{}-{}-{}.log", started, id, host, port);
+        self.transcript = File::create(wire_log.directory.join(name)).ok();
+        self.write(format!("# connection to {}:{}\n", host, port).as_bytes());
+    }
+
+    fn record(&mut self, direction: char, data: &[u8]) {
+        if self.transcript.is_none() {
+            return;
+        }
+        let data = match &self.wire_log {
+            Some(wire_log) if wire_log.redact && direction == '>' => redact_headers(data),
+            _ => data.to_vec(),
+        };
+        let entry = format!(
+            "{} {:.3}s {} bytes\n{}\n",
+            direction,
+            self.opened.elapsed().as_secs_f64(),
+            data.len(),
+            escape_transcript(&data)
+        );
+        self.write(entry.as_bytes());
+    }
+
+    fn write(&mut self, entry: &[u8]) {
+        if let Some(transcript) = &mut self.transcript {
+            let _ = transcript.write_all(entry);
+        }
+    }
+}
+
+impl<S: Socket> Socket for WireLogSocket<S> {
+    fn connect(&mut self, host: &str, port: u16) -> Result<(), String> {
+        self.open_transcript(host, port);
+        self.socket.connect(host, port)
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        self.record('>', data);
+        self.socket.send(data)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+        let count = self.socket.read(buffer)?;
+        if count > 0 {
+            self.record('<', &buffer[..count]);
+        }
+        Ok(count)
+    }
+}
+
+// Replaces the values of credential headers, line by line
+fn redact_headers(data: &[u8]) -> Vec<u8> {
+    let mut redacted = Vec::with_capacity(data.len());
+    for line in data.split_inclusive(|&byte| byte == b'\n') {
+        let colon = line.iter().position(|&byte| byte == b':');
+        let is_credential = colon.is_some_and(|colon| {
+            let name = String::from_utf8_lossy(&line[..colon])
+                .trim()
+                .to_lowercase();
+            REDACTED_HEADERS.contains(&name.as_str())
+        });
+        match colon {
+            Some(colon) if is_credential => {
+                let content = line.trim_ascii_end();
+                redacted.extend_from_slice(&line[..=colon]);
+                redacted.extend_from_slice(b" [redacted]");
+                redacted.extend_from_slice(&line[content.len()..]);
+            }
+            _ => redacted.extend_from_slice(line),
+        }
+    }
+    redacted
+}
+
+// Printable ASCII as is, everything else escaped; a line break follows each \n
+fn escape_transcript(data: &[u8]) -> String {
+    let mut text = String::with_capacity(data.len());
+    for &byte in data {
+        match byte {
+            b'\n' => text.push_str("\\n\n"),
+            b'\r' => text.push_str("\\r"),
+            b'\t' => text.push_str("\\t"),
+            b'\\' => text.push_str("\\\\"),
+            0x20..=0x7e => text.push(byte as char),
+            _ => text.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    text
+}
+
+const READ_CHUNK_SIZE: usize = 4096;
+
+// Buffers reads from any Socket so that protocol code can consume lines,
+// fixed-length bodies, or everything up to EOF without over-reading.
+pub struct SocketReader<S: Socket> {
+    socket: S,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<S: Socket> SocketReader<S> {
+    pub fn new(socket: S) -> Self {
+        SocketReader {
+            socket,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.socket
+    }
+
+    pub fn into_inner(self) -> S {
+        self.socket
+    }
+
+    // Reads another chunk from the socket into the buffer, returning false at EOF
+    fn fill_buffer(&mut self) -> Result<bool, String> {
+        if self.position > 0 {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        let count = self.socket.read(&mut chunk)?;
+        self.buffer.extend_from_slice(&chunk[..count]);
+        Ok(count > 0)
+    }
+
+    pub fn read_line(&mut self) -> Result<String, String> {
         loop {
-            match self.stream.read(&mut buffer) {
-                Ok(0) => {
-                    if line.is_empty() {
-                        return Err("End of file reached".to_string());
-                    } else {
-                        break;
-                    }
-                }
-                Ok(_) => {
-                    let ch = buffer[0] as char;
-                    line.push(ch);
-                    if ch == '\n' {
-                        break;
-                    }
-                }
-                Err(e) => return Err(format!("Failed to read line: {}", e)),
+            let available = &self.buffer[self.position..];
+            if let Some(newline) = available.iter().position(|&b| b == b'\n') {
+                let line = String::from_utf8_lossy(&available[..=newline]).to_string();
+                self.position += newline + 1;
+                return Ok(line);
             }
+            if !self.fill_buffer()? {
+                break;
+            }
+        }
+
+        // EOF without a trailing newline: return what is left, if anything
+        if self.position == self.buffer.len() {
+            return Err("End of file reached".to_string());
         }
-        
+        let line = String::from_utf8_lossy(&self.buffer[self.position..]).to_string();
+        self.position = self.buffer.len();
         Ok(line)
     }
 
-    fn read_to_string(&mut self) -> Result<String, String> {
-        let mut buffer = String::new();
-        self.stream
-            .read_to_string(&mut buffer)
-            .map_err(|e| format!("Failed to read to string: {}", e))?;
-        Ok(buffer)
+    pub fn read_exact(&mut self, length: usize) -> Result<Vec<u8>, String> {
+        while self.buffer.len() - self.position < length {
+            if !self.fill_buffer()? {
+                return Err(format!(
+                    "End of file reached after {} of {} bytes",
+                    self.buffer.len() - self.position,
+                    length
+                ));
+            }
+        }
+        let data = self.buffer[self.position..self.position + length].to_vec();
+        self.position += length;
+        Ok(data)
+    }
+
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>, String> {
+        while self.fill_buffer()? {}
+        let data = self.buffer[self.position..].to_vec();
+        self.position = self.buffer.len();
+        Ok(data)
+    }
+
+    pub fn read_to_string(&mut self) -> Result<String, String> {
+        let data = self.read_to_end()?;
+        Ok(String::from_utf8_lossy(&data).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns at most one chunk per read, to exercise partial reads
+    struct ChunkedSocket {
+        chunks: Vec<Vec<u8>>,
+    }
+
+    impl ChunkedSocket {
+        fn new(chunks: Vec<&[u8]>) -> Self {
+            ChunkedSocket {
+                chunks: chunks.into_iter().rev().map(|c| c.to_vec()).collect(),
+            }
+        }
+    }
+
+    impl Socket for ChunkedSocket {
+        fn connect(&mut self, _host: &str, _port: u16) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn send(&mut self, _data: &[u8]) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<usize, String> {
+            let Some(mut chunk) = self.chunks.pop() else {
+                return Ok(0);
+            };
+            let count = chunk.len().min(buffer.len());
+            buffer[..count].copy_from_slice(&chunk[..count]);
+            if count < chunk.len() {
+                self.chunks.push(chunk.split_off(count));
+            }
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_throttled_socket_passthrough() {
+        let socket = ChunkedSocket::new(vec![b"hello"]);
+        let mut reader = SocketReader::new(ThrottledSocket::new(socket, None));
+
+        let started = Instant::now();
+        assert_eq!(reader.read_to_end().unwrap(), b"hello".to_vec());
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_throttled_socket_adds_latency() {
+        let throttle = Throttle {
+            bytes_per_second: None,
+            latency: Duration::from_millis(20),
+        };
+        let socket = ChunkedSocket::new(vec![b"response"]);
+        let mut socket = ThrottledSocket::new(socket, Some(throttle));
+
+        let started = Instant::now();
+        socket.connect("example.com", 80).unwrap();
+        socket.send(b"request").unwrap();
+        let mut reader = SocketReader::new(socket);
+        assert_eq!(reader.read_to_end().unwrap(), b"response".to_vec());
+
+        // One round trip for the connection and one for the request
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_throttled_socket_limits_bandwidth() {
+        let throttle = Throttle {
+            bytes_per_second: Some(1000),
+            latency: Duration::ZERO,
+        };
+        let data = [b'x'; 100];
+        let socket = ChunkedSocket::new(vec![&data]);
+        let mut reader = SocketReader::new(ThrottledSocket::new(socket, Some(throttle)));
+
+        let started = Instant::now();
+        assert_eq!(reader.read_to_end().unwrap().len(), 100);
+
+        // 100 bytes at 1000 bytes per second
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_throttled_socket_empty_buffer() {
+        let throttle = Throttle {
+            bytes_per_second: Some(1000),
+            latency: Duration::ZERO,
+        };
+        let mut socket = ThrottledSocket::new(ChunkedSocket::new(vec![b"data"]), Some(throttle));
+        assert_eq!(socket.read(&mut []).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_throttled_socket_reconnect_is_free() {
+        let throttle = Throttle {
+            bytes_per_second: None,
+            latency: Duration::from_millis(50),
+        };
+        let mut socket = ThrottledSocket::new(ChunkedSocket::new(vec![]), Some(throttle));
+        socket.connect("example.com", 80).unwrap();
+
+        // A pooled connection is "connected" again for every request it carries
+        let started = Instant::now();
+        socket.connect("example.com", 80).unwrap();
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    fn pooled(chunk: &[u8]) -> PooledSocket {
+        Box::new(ChunkedSocket::new(vec![chunk]))
+    }
+
+    fn read_all(mut socket: PooledSocket) -> Vec<u8> {
+        SocketReader::new(&mut *socket).read_to_end().unwrap()
+    }
+
+    #[test]
+    fn test_connection_pool_reuse() {
+        let pool = ConnectionPool::new(Duration::from_secs(15));
+        assert!(pool.take("http", "example.com", 80).is_none());
+
+        pool.put("http", "example.com", 80, pooled(b"first"));
+        pool.put("http", "example.com", 80, pooled(b"second"));
+        assert_eq!(pool.idle_count("http", "example.com", 80), 2);
+
+        // Connections for another scheme, host or port are kept apart
+        assert!(pool.take("https", "example.com", 80).is_none());
+        assert!(pool.take("http", "example.org", 80).is_none());
+        assert!(pool.take("http", "example.com", 8080).is_none());
+
+        let socket = pool.take("http", "example.com", 80).unwrap();
+        assert_eq!(read_all(socket), b"second".to_vec());
+        assert_eq!(pool.idle_count("http", "example.com", 80), 1);
+    }
+
+    #[test]
+    fn test_connection_pool_idle_timeout() {
+        let pool = ConnectionPool::new(Duration::from_secs(15));
+        let start = Instant::now();
+        pool.put_at("http", "example.com", 80, pooled(b"old"), start);
+        pool.put_at(
+            "http",
+            "example.com",
+            80,
+            pooled(b"new"),
+            start + Duration::from_secs(10),
+        );
+
+        let later = start + Duration::from_secs(20);
+        let socket = pool.take_at("http", "example.com", 80, later).unwrap();
+        assert_eq!(read_all(socket), b"new".to_vec());
+        // The older connection idled out and was dropped
+        assert!(pool.take_at("http", "example.com", 80, later).is_none());
+    }
+
+    #[test]
+    fn test_connection_pool_limit() {
+        let pool = ConnectionPool::new(Duration::from_secs(15));
+        for _ in 0..MAX_IDLE_PER_HOST + 2 {
+            pool.put("http", "example.com", 80, pooled(b""));
+        }
+        assert_eq!(
+            pool.idle_count("http", "example.com", 80),
+            MAX_IDLE_PER_HOST
+        );
+    }
+
+    #[test]
+    fn test_known_hosts_pin_first_certificate() {
+        let mut known_hosts = KnownHosts::new();
+        known_hosts.check("example.org:1965", b"first").unwrap();
+        known_hosts.check("example.org:1965", b"first").unwrap();
+        assert!(known_hosts.check("example.org:1965", b"second").is_err());
+        known_hosts.check("example.org:1966", b"second").unwrap();
+    }
+
+    #[test]
+    fn test_known_hosts_survive_restart() {
+        let directory =
+            std::env::temp_dir().join(format!("learn-browser-known-hosts-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let path = directory.join("known_hosts");
+
+        let mut known_hosts = KnownHosts::new();
+        known_hosts.set_file(&path).unwrap();
+        known_hosts
+            .check("example.org:1965", b"\x30\x82\xff")
+            .unwrap();
+
+        let mut restarted = KnownHosts::new();
+        restarted.check("example.org:1965", b"changed").unwrap();
+        restarted.set_file(&path).unwrap();
+        // The pin from the earlier run replaces the one seen before loading
+        assert!(restarted.check("example.org:1965", b"changed").is_err());
+        restarted
+            .check("example.org:1965", b"\x30\x82\xff")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "example.org:1965\t3082ff\n"
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("00ff7a"), Some(vec![0x00, 0xff, 0x7a]));
+        assert_eq!(decode_hex("0"), None);
+        assert_eq!(decode_hex("zz"), None);
+        assert_eq!(decode_hex("é"), None);
+    }
+
+    fn wire_log_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "learn-browser-wire-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn logged_exchange(wire_log: WireLog) -> String {
+        let socket = ChunkedSocket::new(vec![b"HTTP/1.0 200 OK\r\n", b"\r\n\xff"]);
+        let mut socket = WireLogSocket::new(socket, Some(wire_log.clone()));
+        socket.connect("example.com", 80).unwrap();
+        socket
+            .send(b"GET / HTTP/1.0\r\nCookie: id=42\r\nAuthorization: Basic eDp5\r\n\r\n")
+            .unwrap();
+        SocketReader::new(&mut socket).read_to_end().unwrap();
+
+        let mut files: Vec<_> = fs::read_dir(&wire_log.directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        let transcript = fs::read_to_string(files.pop().unwrap()).unwrap();
+        fs::remove_dir_all(&wire_log.directory).unwrap();
+        transcript
+    }
+
+    #[test]
+    fn test_wire_log_transcript() {
+        let transcript = logged_exchange(WireLog::new(wire_log_directory("redacted")));
+        assert!(transcript.starts_with("# connection to example.com:80\n> "));
+        assert!(transcript.contains("GET / HTTP/1.0\\r\\n\n"));
+        assert!(transcript.contains("Cookie: [redacted]\\r\\n\n"));
+        assert!(transcript.contains("Authorization: [redacted]\\r\\n\n"));
+        assert!(!transcript.contains("eDp5"));
+        assert!(transcript.contains("< "));
+        assert!(transcript.contains("\\r\\n\n\\xff\n"));
+    }
+
+    #[test]
+    fn test_wire_log_without_redaction() {
+        let wire_log = WireLog {
+            redact: false,
+            ..WireLog::new(wire_log_directory("plain"))
+        };
+        let transcript = logged_exchange(wire_log);
+        assert!(transcript.contains("Cookie: id=42"));
+        assert!(transcript.contains("Authorization: Basic eDp5"));
+    }
+
+    #[test]
+    fn test_redact_headers() {
+        assert_eq!(
+            redact_headers(b"GET / HTTP/1.0\r\nhost: a\r\nAUTHORIZATION:x\r\ncookie: y"),
+            b"GET / HTTP/1.0\r\nhost: a\r\nAUTHORIZATION: [redacted]\r\ncookie: [redacted]"
+        );
+    }
+
+    #[test]
+    fn test_read_line_across_chunks() {
+        let socket = ChunkedSocket::new(vec![b"HTTP/1.1 2", b"00 OK\r\nHost", b": a\r\n\r\n"]);
+        let mut reader = SocketReader::new(socket);
+
+        assert_eq!(reader.read_line().unwrap(), "HTTP/1.1 200 OK\r\n");
+        assert_eq!(reader.read_line().unwrap(), "Host: a\r\n");
+        assert_eq!(reader.read_line().unwrap(), "\r\n");
+        assert_eq!(reader.read_line().unwrap_err(), "End of file reached");
+    }
+
+    #[test]
+    fn test_read_line_without_trailing_newline() {
+        let mut reader = SocketReader::new(ChunkedSocket::new(vec![b"last line"]));
+
+        assert_eq!(reader.read_line().unwrap(), "last line");
+        assert_eq!(reader.read_line().unwrap_err(), "End of file reached");
+    }
+
+    #[test]
+    fn test_read_exact_then_rest() {
+        let socket = ChunkedSocket::new(vec![b"\x00\x01", b"\x02\xff\xfe", b"tail"]);
+        let mut reader = SocketReader::new(socket);
+
+        assert_eq!(reader.read_exact(4).unwrap(), vec![0x00, 0x01, 0x02, 0xff]);
+        assert_eq!(reader.read_to_end().unwrap(), b"\xfetail".to_vec());
+    }
+
+    #[test]
+    fn test_read_exact_eof() {
+        let mut reader = SocketReader::new(ChunkedSocket::new(vec![b"abc"]));
+
+        assert_eq!(
+            reader.read_exact(5).unwrap_err(),
+            "End of file reached after 3 of 5 bytes"
+        );
+    }
+
+    #[test]
+    fn test_read_to_string_after_line() {
+        let socket = ChunkedSocket::new(vec![b"20 text/gemini\r\n# Hi", b"\nthere"]);
+        let mut reader = SocketReader::new(socket);
+
+        assert_eq!(reader.read_line().unwrap(), "20 text/gemini\r\n");
+        assert_eq!(reader.read_to_string().unwrap(), "# Hi\nthere");
     }
 }